@@ -0,0 +1,88 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+#[derive(Default)]
+struct Attrs {
+    skip: bool,
+    rename: Option<String>,
+    path: Option<String>,
+}
+
+// Reads `skip`, `rename = ".."` and `path = ".."` out of the attributes named
+// `name`, the others tabled and serde take are left alone.
+fn read(field: &syn::Field, name: &str, attrs: &mut Attrs) -> Result<(), syn::Error> {
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident(name)) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                attrs.skip = true;
+            } else if meta.path.is_ident("rename") {
+                attrs.rename = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.path.is_ident("path") {
+                attrs.path = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let _args;
+                syn::parenthesized!(_args in meta.input);
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
+
+pub fn derive_columns(input: syn::DeriveInput) -> Result<TokenStream, syn::Error> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(ref fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "Columns can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    // Tabled keeps every field that isn't skipped, in order. They are found in
+    // the serialized item under their serde name.
+    let mut paths = vec![];
+    for field in fields {
+        let mut tabled = Attrs::default();
+        read(field, "tabled", &mut tabled)?;
+        if tabled.skip {
+            continue;
+        }
+
+        let mut serde = Attrs::default();
+        read(field, "serde", &mut serde)?;
+        let mut columns = Attrs::default();
+        read(field, "columns", &mut columns)?;
+
+        let field_name = field
+            .ident
+            .as_ref()
+            .map(|i| i.to_string())
+            .unwrap_or_default();
+        paths.push(
+            columns
+                .path
+                .unwrap_or_else(|| format!(".{}", serde.rename.unwrap_or(field_name))),
+        );
+    }
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics crate::output::Columns for #name #ty_generics #where_clause {
+            fn paths() -> Vec<&'static str> {
+                vec![#(#paths),*]
+            }
+        }
+    })
+}
@@ -1,3 +1,4 @@
+mod columns;
 mod container;
 
 use proc_macro::TokenStream;
@@ -8,3 +9,12 @@ pub fn derive_container(input: TokenStream) -> TokenStream {
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
+
+/// Implements `output::Columns` from the `tabled` attributes of a struct.
+/// `#[columns(path = ".a.b")]` points a column at a nested field.
+#[proc_macro_derive(Columns, attributes(columns))]
+pub fn derive_columns(input: TokenStream) -> TokenStream {
+    columns::derive_columns(syn::parse_macro_input!(input))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
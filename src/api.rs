@@ -1,4 +1,4 @@
-mod utils;
+pub mod utils;
 
 use std::collections::HashMap;
 
@@ -7,9 +7,13 @@ use strum::{Display, EnumString};
 use tabled::Tabled;
 use utoipa::ToSchema;
 
-use crate::{api::utils::deserialize_labels_eq_filter, output::tabled::Option};
+use crate::{
+    api::utils::{deserialize_labels_eq_filter, serialize_labels_eq_filter},
+    derive::Columns,
+    output::tabled::{Bytes, Option, Timestamp},
+};
 
-#[derive(Default, Debug, Clone, Serialize, Deserialize, ToSchema, Tabled)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, ToSchema, Tabled, Columns)]
 pub struct DataNamespace {
     pub name: String,
     #[tabled(display_with = "crate::output::tabled::display")]
@@ -21,7 +25,7 @@ pub struct ListNamespacesResponse {
     pub namespaces: Vec<DataNamespace>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Tabled)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Tabled, Columns)]
 pub struct GetNamespaceResponse {
     pub namespace: DataNamespace, /*  */
 }
@@ -30,11 +34,13 @@ pub struct GetNamespaceResponse {
 #[derive(Default, Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreateNamespace {
     pub name: String,
+    #[serde(default)]
     pub extraction_graphs: Vec<ExtractionGraph>,
+    #[serde(default)]
     pub labels: HashMap<String, String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Tabled)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Tabled, Columns)]
 pub struct ExtractionGraph {
     #[serde(default)]
     #[tabled(skip)]
@@ -55,13 +61,13 @@ impl std::fmt::Display for ExtractionGraph {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Tabled)]
+#[derive(Debug, Serialize, Deserialize, Tabled, Columns)]
 pub struct ExtractionGraphResponse {
     #[tabled(display_with = "crate::output::tabled::display")]
     pub indexes: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Tabled)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Tabled, Columns)]
 pub struct ExtractionPolicy {
     #[serde(default)]
     pub id: String,
@@ -83,7 +89,7 @@ impl std::fmt::Display for ExtractionPolicy {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Tabled)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Tabled, Columns)]
 pub struct ExtractorDescription {
     pub name: String,
     #[tabled(display_with = "crate::output::tabled::display")]
@@ -137,7 +143,7 @@ pub enum IndexDistance {
     Euclidean,
 }
 
-#[derive(Debug, Serialize, Deserialize, ToSchema, Tabled)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, Tabled, Columns)]
 pub struct Index {
     pub name: String,
     pub embedding_schema: EmbeddingSchema,
@@ -148,18 +154,77 @@ pub struct ListIndexesResponse {
     pub indexes: Vec<Index>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SearchRequest {
+    pub index: String,
+    pub query: String,
+    pub k: std::option::Option<u64>,
+    #[serde(default)]
+    pub filters: Vec<String>,
+    #[serde(default)]
+    pub include_content: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct IndexSearchResponse {
+    pub results: Vec<DocumentFragment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Tabled, Columns)]
+pub struct DocumentFragment {
+    pub content_id: String,
+    #[tabled(rename = "score")]
+    pub confidence_score: f32,
+    #[tabled(display_with = "crate::output::tabled::snippet")]
+    pub text: String,
+    #[serde(default)]
+    #[tabled(display_with = "crate::output::tabled::labels")]
+    pub labels: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    #[tabled(skip)]
+    pub mime_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema, Clone)]
+pub struct ListContent {
+    #[serde(skip_serializing_if = "std::option::Option::is_none")]
+    pub graph: std::option::Option<String>,
+    #[serde(skip_serializing_if = "std::option::Option::is_none")]
+    pub source: std::option::Option<String>,
+    #[serde(skip_serializing_if = "std::option::Option::is_none")]
+    pub parent_id: std::option::Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "std::option::Option::is_none",
+        serialize_with = "serialize_labels_eq_filter",
+        deserialize_with = "deserialize_labels_eq_filter"
+    )]
+    pub labels_eq: std::option::Option<HashMap<String, serde_json::Value>>,
+    #[serde(skip_serializing_if = "std::option::Option::is_none")]
+    pub start_id: std::option::Option<String>,
+    #[serde(skip_serializing_if = "std::option::Option::is_none")]
+    pub limit: std::option::Option<u64>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub return_total: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
 pub struct ListContentResponse {
     pub content_list: Vec<ContentMetadata>,
     pub total: u64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateLabelsRequest {
+    pub labels: HashMap<String, serde_json::Value>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct GetContentMetadataResponse {
     pub content_metadata: ContentMetadata,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default, ToSchema, Clone, Tabled)]
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema, Clone, PartialEq, Tabled, Columns)]
 pub struct ContentMetadata {
     pub id: String,
     #[tabled(skip)]
@@ -170,17 +235,114 @@ pub struct ContentMetadata {
     pub namespace: String,
     pub name: String,
     pub mime_type: String,
-    // TODO: this feels like it should be in the table.
-    #[tabled(skip)]
+    #[tabled(display_with = "crate::output::tabled::labels")]
     pub labels: HashMap<String, serde_json::Value>,
     #[tabled(display_with = "crate::output::tabled::display")]
     pub extraction_graph_names: Vec<String>,
     #[tabled(skip)]
     pub storage_url: String,
-    // TODO: convert into something that can be displayed.
-    pub created_at: i64,
+    #[schema(value_type = i64)]
+    pub created_at: Timestamp,
     pub source: String,
-    pub size: u64,
+    #[schema(value_type = u64)]
+    pub size: Bytes,
     #[tabled(skip)]
     pub hash: String,
 }
+
+impl std::fmt::Display for ContentMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+#[derive(
+    Display, EnumString, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema,
+)]
+pub enum TaskOutcome {
+    #[strum(serialize = "unknown")]
+    Unknown,
+    #[strum(serialize = "success")]
+    Success,
+    #[strum(serialize = "failed")]
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema, Clone)]
+pub struct ListTasks {
+    #[serde(skip_serializing_if = "std::option::Option::is_none")]
+    pub extraction_policy: std::option::Option<String>,
+    #[serde(skip_serializing_if = "std::option::Option::is_none")]
+    pub content_id: std::option::Option<String>,
+    #[serde(skip_serializing_if = "std::option::Option::is_none")]
+    pub outcome: std::option::Option<TaskOutcome>,
+    #[serde(skip_serializing_if = "std::option::Option::is_none")]
+    pub start_id: std::option::Option<String>,
+    #[serde(skip_serializing_if = "std::option::Option::is_none")]
+    pub limit: std::option::Option<u64>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub return_total: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
+pub struct ListTasksResponse {
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub total: std::option::Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GetTaskResponse {
+    pub task: Task,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, PartialEq, Tabled, Columns)]
+pub struct Task {
+    pub id: String,
+    pub extractor: String,
+    #[tabled(rename = "extraction_policy")]
+    pub extraction_policy_id: String,
+    #[tabled(rename = "graph")]
+    pub extraction_graph_name: String,
+    #[serde(default)]
+    #[tabled(skip)]
+    pub output_index_table_mapping: HashMap<String, String>,
+    #[tabled(skip)]
+    pub namespace: String,
+    #[tabled(rename = "content_id")]
+    #[columns(path = ".content_metadata.id")]
+    pub content_metadata: ContentMetadata,
+    #[serde(default)]
+    #[tabled(skip)]
+    pub input_params: serde_json::Value,
+    pub outcome: TaskOutcome,
+    #[serde(default)]
+    #[tabled(skip)]
+    pub index_tables: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_tasks_query() {
+        let query = ListTasks {
+            content_id: Some("abc".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            serde_json::to_value(&query).unwrap(),
+            serde_json::json!({"content_id": "abc"})
+        );
+        assert_eq!(
+            serde_json::to_value(ListTasks {
+                return_total: true,
+                ..query
+            })
+            .unwrap(),
+            serde_json::json!({"content_id": "abc", "return_total": true})
+        );
+    }
+}
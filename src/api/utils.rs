@@ -10,13 +10,13 @@ pub fn validate_label_key(key: &str) -> Result<()> {
         (
             key.chars()
                 .next()
-                .map_or(false, |c| c.is_ascii_alphanumeric()),
+                .is_some_and(|c| c.is_ascii_alphanumeric()),
             "must begin with an alphanumeric character",
         ),
         (
             key.chars()
                 .last()
-                .map_or(false, |c| c.is_ascii_alphanumeric()),
+                .is_some_and(|c| c.is_ascii_alphanumeric()),
             "must end with an alphanumeric character",
         ),
         (
@@ -87,14 +87,14 @@ pub fn validate_label_value(value: &str) -> Result<()> {
             value
                 .chars()
                 .next()
-                .map_or(false, |c| c.is_ascii_alphanumeric()),
+                .is_some_and(|c| c.is_ascii_alphanumeric()),
             "must begin with an alphanumeric character",
         ),
         (
             value
                 .chars()
                 .last()
-                .map_or(false, |c| c.is_ascii_alphanumeric()),
+                .is_some_and(|c| c.is_ascii_alphanumeric()),
             "must end with an alphanumeric character",
         ),
         (
@@ -187,3 +187,120 @@ where
 
     Ok(Some(labels_eq))
 }
+
+// The inverse of deserialize_labels_eq_filter, for sending filters to the
+// server.
+pub fn serialize_labels_eq_filter<S>(
+    labels: &Option<HashMap<String, serde_json::Value>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    let Some(labels) = labels else {
+        return serializer.serialize_none();
+    };
+
+    let mut labels: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::String(value) => format!("{}:{}", key, value),
+            value => format!("{}:{}", key, value),
+        })
+        .collect();
+    labels.sort();
+
+    serializer.serialize_str(&labels.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ListContent;
+
+    fn labels_eq(labels: &[(&str, serde_json::Value)]) -> ListContent {
+        ListContent {
+            labels_eq: Some(
+                labels
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.clone()))
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn label_keys() {
+        for key in ["a", "topic", "file.name", "x-1_2"] {
+            assert!(validate_label_key(key).is_ok(), "{}", key);
+        }
+
+        for key in ["", "-a", "a-", "a b", "é", &"a".repeat(64)] {
+            assert!(validate_label_key(key).is_err(), "{}", key);
+        }
+    }
+
+    #[test]
+    fn label_values() {
+        for value in ["", "1", "news", "2024.01-a_b"] {
+            assert!(validate_label_value(value).is_ok(), "{}", value);
+        }
+
+        for value in ["_a", "a.", "a/b", "ü", &"1".repeat(64)] {
+            assert!(validate_label_value(value).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn raw_labels() {
+        assert_eq!(
+            parse_validate_label_raw("a:1").unwrap(),
+            ("a".to_string(), "1".to_string())
+        );
+        assert_eq!(
+            parse_validate_label_raw("a:").unwrap(),
+            ("a".to_string(), "".to_string())
+        );
+        assert!(parse_validate_label_raw("a").is_err());
+        assert!(parse_validate_label_raw("a:b:c").is_err());
+    }
+
+    #[test]
+    fn labels_eq_on_the_wire() {
+        let request = reqwest::Client::new()
+            .get("http://localhost/content")
+            .query(&labels_eq(&[
+                ("b", serde_json::json!("x")),
+                ("a", serde_json::json!(1)),
+            ]))
+            .build()
+            .unwrap();
+
+        assert_eq!(request.url().query(), Some("labels_eq=a%3A1%2Cb%3Ax"));
+    }
+
+    #[test]
+    fn labels_eq_round_trip() {
+        let query = labels_eq(&[
+            ("a", serde_json::json!(1)),
+            ("b", serde_json::json!("x")),
+            ("c", serde_json::json!(true)),
+        ]);
+
+        let parsed: ListContent =
+            serde_json::from_value(serde_json::to_value(&query).unwrap()).unwrap();
+
+        assert_eq!(parsed.labels_eq, query.labels_eq);
+    }
+
+    #[test]
+    fn labels_eq_rejects_invalid_labels() {
+        for labels in ["", "a", "a:1,a:2", "-a:1", "a:b/c"] {
+            let parsed =
+                serde_json::from_value::<ListContent>(serde_json::json!({ "labels_eq": labels }));
+
+            assert!(parsed.is_err(), "{}", labels);
+        }
+    }
+}
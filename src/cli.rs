@@ -1,6 +1,10 @@
+pub mod apply;
+pub mod config;
 pub mod content;
+pub mod diff;
 pub mod extractor;
 pub mod graph;
 pub mod index;
 pub mod namespace;
 pub mod root;
+pub mod task;
@@ -0,0 +1,328 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use clap::Parser;
+use eyre::{eyre, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+use tabled::Tabled;
+use tracing::{info, instrument};
+
+use crate::{
+    api::{CreateNamespace, DataNamespace, ExtractionGraph},
+    client,
+    command::Command,
+    derive::{Columns, Command},
+    file, output,
+};
+
+/// Create the namespaces and graphs described in files, skipping any that
+/// already exist
+#[derive(Debug, Parser, Command)]
+pub struct Apply {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
+    #[clap(from_global)]
+    pub namespace: String,
+
+    /// File or directory of namespace and graph definitions, can be repeated
+    #[clap(short, long = "file", required = true)]
+    pub files: Vec<PathBuf>,
+
+    /// Only show the plan
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+/// A document passed to `apply`. The kind is taken from a `kind` field when
+/// there is one, otherwise from the fields only one of them has:
+/// `extraction_policies` for graphs and `extraction_graphs` for namespaces.
+#[derive(Debug, Clone)]
+pub enum Resource {
+    Namespace(CreateNamespace),
+    Graph(ExtractionGraph),
+}
+
+impl<'de> Deserialize<'de> for Resource {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+
+        let kind = match value.as_object_mut() {
+            Some(doc) => match doc.remove("kind") {
+                Some(serde_json::Value::String(kind)) => kind.to_lowercase(),
+                Some(kind) => return Err(D::Error::custom(format!("invalid kind: {}", kind))),
+                None => match (
+                    doc.contains_key("extraction_policies"),
+                    doc.contains_key("extraction_graphs"),
+                ) {
+                    (true, false) => "graph".to_string(),
+                    (false, true) => "namespace".to_string(),
+                    _ => {
+                        return Err(D::Error::custom(
+                            "can't tell a namespace from a graph, set kind",
+                        ))
+                    }
+                },
+            },
+            None => return Err(D::Error::custom("expected a namespace or graph")),
+        };
+
+        match kind.as_str() {
+            "namespace" => serde_json::from_value(value)
+                .map(Resource::Namespace)
+                .map_err(D::Error::custom),
+            "graph" | "extractiongraph" | "extraction_graph" => serde_json::from_value(value)
+                .map(Resource::Graph)
+                .map_err(D::Error::custom),
+            kind => Err(D::Error::custom(format!("unknown kind: {}", kind))),
+        }
+    }
+}
+
+/// A line of the plan.
+#[derive(Debug, Serialize, Tabled, Columns)]
+pub struct Change {
+    /// `create`, `exists`, or `differs` when the definition doesn't match the
+    /// server. Existing resources are never changed.
+    pub action: &'static str,
+    pub kind: &'static str,
+    pub namespace: String,
+    pub name: String,
+}
+
+enum Step {
+    Namespace(CreateNamespace),
+    Graph(String, ExtractionGraph),
+}
+
+// Graphs can't be updated, only compare what the policies are.
+fn same(a: &ExtractionGraph, b: &ExtractionGraph) -> bool {
+    let policies = |g: &ExtractionGraph| {
+        let mut policies: Vec<(String, String)> = g
+            .extraction_policies
+            .iter()
+            .map(|p| (p.name.clone(), p.extractor.clone()))
+            .collect();
+        policies.sort();
+
+        policies
+    };
+
+    policies(a) == policies(b)
+}
+
+impl Apply {
+    fn plan_graph(
+        &self,
+        known: &mut HashMap<String, Vec<ExtractionGraph>>,
+        namespace: &str,
+        graph: ExtractionGraph,
+        plan: &mut Vec<(Change, Option<Step>)>,
+    ) -> Result<()> {
+        let Some(graphs) = known.get_mut(namespace) else {
+            return Err(eyre!(
+                "Namespace {} does not exist, it must be created before graph {}",
+                namespace,
+                graph.name
+            ));
+        };
+
+        let (action, step) = match graphs.iter().find(|g| g.name == graph.name) {
+            Some(existing) if same(existing, &graph) => ("exists", None),
+            Some(_) => ("differs", None),
+            None => {
+                graphs.push(graph.clone());
+
+                (
+                    "create",
+                    Some(Step::Graph(namespace.to_string(), graph.clone())),
+                )
+            }
+        };
+
+        plan.push((
+            Change {
+                action,
+                kind: "graph",
+                namespace: namespace.to_string(),
+                name: graph.name,
+            },
+            step,
+        ));
+
+        Ok(())
+    }
+
+    // Namespaces are planned first so that graphs can be applied to them.
+    fn plan(
+        &self,
+        existing: Vec<DataNamespace>,
+        mut resources: Vec<Resource>,
+    ) -> Result<Vec<(Change, Option<Step>)>> {
+        let mut known: HashMap<String, Vec<ExtractionGraph>> = existing
+            .into_iter()
+            .map(|ns| (ns.name, ns.extraction_graphs))
+            .collect();
+
+        resources.sort_by_key(|r| matches!(r, Resource::Graph(_)));
+
+        let mut plan = vec![];
+        for resource in resources {
+            match resource {
+                Resource::Namespace(ns) if known.contains_key(&ns.name) => {
+                    plan.push((
+                        Change {
+                            action: "exists",
+                            kind: "namespace",
+                            namespace: ns.name.clone(),
+                            name: ns.name.clone(),
+                        },
+                        None,
+                    ));
+
+                    for graph in ns.extraction_graphs {
+                        self.plan_graph(&mut known, &ns.name, graph, &mut plan)?;
+                    }
+                }
+                // Graphs defined on a new namespace are created along with it.
+                Resource::Namespace(ns) => {
+                    known.insert(ns.name.clone(), ns.extraction_graphs.clone());
+
+                    plan.push((
+                        Change {
+                            action: "create",
+                            kind: "namespace",
+                            namespace: ns.name.clone(),
+                            name: ns.name.clone(),
+                        },
+                        Some(Step::Namespace(ns.clone())),
+                    ));
+
+                    plan.extend(ns.extraction_graphs.iter().map(|graph| {
+                        (
+                            Change {
+                                action: "create",
+                                kind: "graph",
+                                namespace: ns.name.clone(),
+                                name: graph.name.clone(),
+                            },
+                            None,
+                        )
+                    }));
+                }
+                Resource::Graph(graph) => {
+                    let namespace = match graph.namespace.as_str() {
+                        "" => self.namespace.clone(),
+                        namespace => namespace.to_string(),
+                    };
+
+                    self.plan_graph(&mut known, &namespace, graph, &mut plan)?;
+                }
+            }
+        }
+
+        Ok(plan)
+    }
+}
+
+#[async_trait::async_trait]
+impl Command for Apply {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let mut resources = vec![];
+        for path in &self.files {
+            resources.extend(file::documents::<Resource>(path)?);
+        }
+
+        let existing: Vec<DataNamespace> = self.api_server.list().await?;
+        let plan = self.plan(existing, resources)?;
+
+        let (changes, steps): (Vec<_>, Vec<_>) = plan.into_iter().unzip();
+        self.output.list(&changes)?;
+
+        if self.dry_run {
+            return Ok(());
+        }
+
+        for step in steps.into_iter().flatten() {
+            match step {
+                Step::Namespace(ns) => {
+                    info!(namespace = ns.name, "creating namespace");
+
+                    self.api_server.create(&ns).await?;
+                }
+                Step::Graph(namespace, mut graph) => {
+                    info!(namespace, graph = graph.name, "creating graph");
+
+                    graph.namespace.clone_from(&namespace);
+                    self.api_server
+                        .clone()
+                        .with_namespace(&namespace)
+                        .create(&graph)
+                        .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> Result<Resource, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+
+    #[test]
+    fn namespace_by_kind() {
+        let resource = parse("kind: Namespace\nname: foo").unwrap();
+
+        assert!(matches!(resource, Resource::Namespace(ns) if ns.name == "foo"));
+    }
+
+    #[test]
+    fn namespace_by_graphs() {
+        let resource = parse("name: foo\nextraction_graphs: []").unwrap();
+
+        assert!(matches!(resource, Resource::Namespace(ns) if ns.name == "foo"));
+    }
+
+    #[test]
+    fn graph_by_kind() {
+        let resource = parse("kind: extraction_graph\nname: bar\nextraction_policies: []").unwrap();
+
+        assert!(matches!(resource, Resource::Graph(g) if g.name == "bar"));
+    }
+
+    #[test]
+    fn graph_by_policies() {
+        let yaml =
+            "name: bar\nextraction_policies:\n  - extractor: tensorlake/minilm-l6\n    name: embed";
+        let resource = parse(yaml).unwrap();
+
+        assert!(matches!(resource, Resource::Graph(g) if g.extraction_policies.len() == 1));
+    }
+
+    #[test]
+    fn ambiguous_without_kind() {
+        assert!(parse("name: foo").is_err());
+        assert!(parse("name: foo\nextraction_graphs: []\nextraction_policies: []").is_err());
+    }
+
+    #[test]
+    fn invalid_kind() {
+        assert!(parse("kind: 1\nname: foo").is_err());
+        assert!(parse("kind: index\nname: foo").is_err());
+        assert!(parse("- name: foo").is_err());
+    }
+}
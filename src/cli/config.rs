@@ -0,0 +1,213 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use eyre::Result;
+use tracing::instrument;
+
+use crate::{command::Command, config, derive::Command, output};
+
+/// Manage named server contexts
+#[derive(Debug, Parser, Command)]
+pub struct Config {
+    #[command(subcommand)]
+    pub cmd: ConfigCmd,
+}
+
+#[derive(Debug, Subcommand, Command)]
+pub enum ConfigCmd {
+    CurrentContext(CurrentContext),
+    DeleteContext(DeleteContext),
+    GetContexts(GetContexts),
+    SetContext(SetContext),
+    UseContext(UseContext),
+    View(View),
+}
+
+impl Command for Config {}
+
+fn path(config: &Option<PathBuf>) -> Result<PathBuf> {
+    match config {
+        Some(path) => Ok(path.clone()),
+        None => config::Config::path(),
+    }
+}
+
+/// Print the name of the current context
+#[derive(Debug, Parser, Command)]
+pub struct CurrentContext {
+    #[clap(from_global)]
+    pub config: Option<PathBuf>,
+}
+
+#[async_trait::async_trait]
+impl Command for CurrentContext {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        match config::Config::load(&path(&self.config)?)?.current_context {
+            Some(name) => println!("{}", name),
+            None => return Err(eyre::eyre!("current context is not set")),
+        }
+
+        Ok(())
+    }
+}
+
+/// Remove a context
+#[derive(Debug, Parser, Command)]
+pub struct DeleteContext {
+    #[clap(from_global)]
+    pub config: Option<PathBuf>,
+
+    /// Name of the context
+    pub name: String,
+}
+
+#[async_trait::async_trait]
+impl Command for DeleteContext {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let path = path(&self.config)?;
+        let mut cfg = config::Config::load(&path)?;
+
+        if cfg.contexts.remove(&self.name).is_none() {
+            return Err(eyre::eyre!("context not found: {}", self.name));
+        }
+
+        if cfg.current_context.as_ref() == Some(&self.name) {
+            cfg.current_context = None;
+        }
+
+        cfg.save(&path)
+    }
+}
+
+/// List all the contexts, the current one is marked with a `*`
+#[derive(Debug, Parser, Command)]
+pub struct GetContexts {
+    #[clap(from_global)]
+    pub config: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+}
+
+#[async_trait::async_trait]
+impl Command for GetContexts {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        self.output
+            .list(&config::Config::load(&path(&self.config)?)?.summaries())
+    }
+}
+
+/// Create or update a context, only the values passed are changed
+#[derive(Debug, Parser, Command)]
+pub struct SetContext {
+    #[clap(from_global)]
+    pub config: Option<PathBuf>,
+
+    /// Name of the context
+    pub name: String,
+
+    /// Values to set as KEY=VALUE. Valid keys are api-server, namespace,
+    /// token, token-file, token-command, api-key, api-key-header and output
+    #[arg(value_parser = parse_setting)]
+    pub values: Vec<(String, String)>,
+
+    /// Also make this the current context
+    #[arg(long)]
+    pub current: bool,
+}
+
+// Flags would clash with the globals on Root, which clap propagates into any
+// argument sharing their name.
+fn parse_setting(raw: &str) -> Result<(String, String)> {
+    match raw.split_once('=') {
+        Some((key, value)) => Ok((key.to_string(), value.to_string())),
+        None => Err(eyre::eyre!("expected KEY=VALUE, got {}", raw)),
+    }
+}
+
+#[async_trait::async_trait]
+impl Command for SetContext {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let values: serde_json::Map<_, _> = self
+            .values
+            .iter()
+            .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+            .collect();
+
+        let update: config::Context =
+            serde_path_to_error::deserialize(serde_json::Value::Object(values))?;
+
+        if let Some(url) = &update.api_server {
+            reqwest::Url::parse(url)?;
+        }
+
+        let path = path(&self.config)?;
+        let mut cfg = config::Config::load(&path)?;
+
+        cfg.contexts
+            .entry(self.name.clone())
+            .or_default()
+            .merge(update);
+
+        if self.current || cfg.current_context.is_none() {
+            cfg.current_context = Some(self.name.clone());
+        }
+
+        cfg.save(&path)
+    }
+}
+
+/// Switch the current context
+#[derive(Debug, Parser, Command)]
+pub struct UseContext {
+    #[clap(from_global)]
+    pub config: Option<PathBuf>,
+
+    /// Name of the context
+    pub name: String,
+}
+
+#[async_trait::async_trait]
+impl Command for UseContext {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let path = path(&self.config)?;
+        let mut cfg = config::Config::load(&path)?;
+
+        cfg.context(&self.name)?;
+        cfg.current_context = Some(self.name.clone());
+
+        cfg.save(&path)
+    }
+}
+
+/// Show the config file, credentials are redacted unless --raw is passed
+#[derive(Debug, Parser, Command)]
+pub struct View {
+    #[clap(from_global)]
+    pub config: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
+    /// Include credentials
+    #[arg(long)]
+    pub raw: bool,
+}
+
+#[async_trait::async_trait]
+impl Command for View {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let mut cfg = config::Config::load(&path(&self.config)?)?;
+        if !self.raw {
+            cfg = cfg.redacted();
+        }
+
+        self.output.document(&cfg)
+    }
+}
@@ -1,12 +1,31 @@
-use std::{fmt::Write, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
 use clap::{Parser, Subcommand};
-use eyre::Result;
-use indicatif::{ProgressBar, ProgressState, ProgressStyle};
-use tokio::{fs::File, io::AsyncRead};
+use eyre::{eyre, Result};
+use futures::{stream, StreamExt};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressState, ProgressStyle};
+use ring::digest;
+use serde::Serialize;
+use tabled::Tabled;
+use tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+};
 use tracing::{info, instrument};
 
-use crate::{api, client, command::Command, derive::Command, output};
+use crate::{
+    api, client,
+    command::{self, Command},
+    derive::{Columns, Command},
+    output,
+};
 
 /// Work with content, such as downloading it or examining its metadata
 #[derive(Debug, Parser, Command)]
@@ -18,10 +37,15 @@ pub struct Content {
 #[derive(Debug, Subcommand, Command)]
 pub enum ContentCmd {
     Delete(Delete),
+    Describe(Describe),
     Download(Download),
+    Export(Export),
     Get(Get),
+    Labels(Labels),
     List(List),
+    Tree(Tree),
     Upload(Upload),
+    Wait(Wait),
 }
 
 impl Command for Content {}
@@ -29,10 +53,10 @@ impl Command for Content {}
 /// Delete a piece of content
 #[derive(Debug, Parser, Command)]
 pub struct Delete {
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub api_server: client::Client,
 
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub output: output::Format,
 
     #[clap(from_global)]
@@ -55,13 +79,14 @@ impl Command for Delete {
     }
 }
 
-/// Download a piece of content locally
+/// Download a piece of content locally, its size and hash are checked against
+/// the metadata once it has been written
 #[derive(Debug, Parser, Command)]
 pub struct Download {
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub api_server: client::Client,
 
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub output: output::Format,
 
     #[clap(from_global)]
@@ -73,37 +98,51 @@ pub struct Download {
     /// Path to save the content to
     #[clap(short, long)]
     pub file: Option<PathBuf>,
+
+    /// Continue an interrupted download of --file instead of starting over
+    #[clap(long, requires = "file")]
+    pub resume: bool,
 }
 
 impl Download {
-    async fn to_file<W>(&self, path: &PathBuf, mut reader: W) -> Result<()>
-    where
-        W: AsyncRead + std::marker::Unpin,
-    {
-        let mut writer = File::options()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(path)
-            .await?;
+    async fn to_file(
+        &self,
+        client: &client::Client,
+        content: &api::ContentMetadata,
+        path: &Path,
+    ) -> Result<()> {
+        let progress = match content.size.0 {
+            0 => self.spinner()?,
+            size => self.progress_bar(size)?,
+        };
 
-        tokio::io::copy(&mut reader, &mut writer).await?;
+        save(client, content, path, self.resume, &progress).await?;
+        progress.finish_and_clear();
 
         Ok(())
     }
 
-    async fn to_stdout<W>(&self, mut reader: W) -> Result<()>
-    where
-        W: AsyncRead + std::marker::Unpin,
-    {
-        let mut writer = tokio::io::stdout();
+    async fn to_stdout(
+        &self,
+        client: &client::Client,
+        content: &api::ContentMetadata,
+    ) -> Result<()> {
+        let stream = client
+            .get_stream::<client::types::Download>(Some(&self.id), 0)
+            .await?;
 
-        tokio::io::copy(&mut reader, &mut writer).await?;
+        let mut digest = digest::Context::new(&digest::SHA256);
+        let mut reader = stream.reader;
+        let len = copy(&mut reader, &mut tokio::io::stdout(), &mut digest).await?;
 
-        Ok(())
+        verify(content, len, digest.finish())
     }
 
     fn progress_bar(&self, size: u64) -> Result<ProgressBar> {
+        if !self.output.progress() {
+            return Ok(ProgressBar::hidden());
+        }
+
         let pb = ProgressBar::new(size);
         pb.set_style(
             indicatif::ProgressStyle::default_bar()
@@ -121,6 +160,10 @@ impl Download {
     }
 
     fn spinner(&self) -> Result<ProgressBar> {
+        if !self.output.progress() {
+            return Ok(ProgressBar::hidden());
+        }
+
         let pb = ProgressBar::new_spinner();
         pb.set_style(
             ProgressStyle::default_spinner()
@@ -131,38 +174,401 @@ impl Download {
     }
 }
 
+// Content is written next to the destination and only renamed into place once
+// it has been verified, this is also what `resume` continues from.
+fn part_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+
+    path.with_file_name(name)
+}
+
+async fn save(
+    client: &client::Client,
+    content: &api::ContentMetadata,
+    path: &Path,
+    resume: bool,
+    progress: &ProgressBar,
+) -> Result<()> {
+    let part = part_path(path);
+    let mut digest = digest::Context::new(&digest::SHA256);
+
+    let mut offset = 0;
+    if resume {
+        match File::open(&part).await {
+            Ok(mut existing) => {
+                offset = copy(&mut existing, &mut tokio::io::sink(), &mut digest).await?;
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        if offset > content.size.0 {
+            offset = 0;
+            digest = digest::Context::new(&digest::SHA256);
+        }
+    }
+
+    // Nothing left to fetch when the previous attempt stopped just before the
+    // rename.
+    let mut len = offset;
+    if offset == 0 || offset < content.size.0 {
+        let stream = client
+            .get_stream::<client::types::Download>(Some(&content.id), offset)
+            .await?;
+
+        if stream.offset != offset {
+            info!(offset, "server does not support resuming, starting over");
+            digest = digest::Context::new(&digest::SHA256);
+        }
+
+        if let Some(size) = stream.size {
+            progress.set_length(size);
+        }
+        progress.set_position(stream.offset);
+
+        let mut writer = File::options()
+            .create(true)
+            .write(true)
+            .append(stream.offset > 0)
+            .truncate(stream.offset == 0)
+            .open(&part)
+            .await?;
+
+        len = stream.offset
+            + copy(
+                &mut progress.wrap_async_read(stream.reader),
+                &mut writer,
+                &mut digest,
+            )
+            .await?;
+
+        writer.sync_all().await?;
+    }
+
+    if let Err(e) = verify(content, len, digest.finish()) {
+        // Continuing from corrupt data would never succeed.
+        tokio::fs::remove_file(&part).await?;
+
+        return Err(e);
+    }
+
+    Ok(tokio::fs::rename(&part, path).await?)
+}
+
+// Like tokio::io::copy, but hashes everything that passes through.
+async fn copy<R, W>(reader: &mut R, writer: &mut W, digest: &mut digest::Context) -> Result<u64>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut buf = vec![0; 64 * 1024];
+    let mut len = 0;
+
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+
+        digest.update(&buf[..n]);
+        writer.write_all(&buf[..n]).await?;
+        len += n as u64;
+    }
+
+    writer.flush().await?;
+
+    Ok(len)
+}
+
+// The server records the hex encoded SHA-256 of the content. Content created
+// by older servers may be missing the size and hash, those are skipped.
+fn verify(content: &api::ContentMetadata, len: u64, digest: digest::Digest) -> Result<()> {
+    if content.size.0 > 0 && len != content.size.0 {
+        return Err(eyre!(
+            "size mismatch for {}: expected {} bytes, received {}",
+            content.id,
+            content.size.0,
+            len
+        ));
+    }
+
+    let hash: String = digest
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    if !content.hash.is_empty() && !content.hash.eq_ignore_ascii_case(&hash) {
+        return Err(eyre!(
+            "hash mismatch for {}: expected {}, received {}",
+            content.id,
+            content.hash,
+            hash
+        ));
+    }
+
+    Ok(())
+}
+
 #[async_trait::async_trait]
 impl Command for Download {
     #[allow(clippy::blocks_in_conditions)]
     // #[instrument(err, "content::download", target = "telemetry")]
     #[instrument(err, fields(activity = "content::download"))]
     async fn run(&self) -> Result<()> {
-        let (size, reader) = self
+        let client = self.api_server.clone().with_namespace(&self.namespace);
+        let content: api::ContentMetadata = client.get(&self.id).await?;
+
+        match &self.file {
+            Some(path) => self.to_file(&client, &content, path).await,
+            None => self.to_stdout(&client, &content).await,
+        }
+    }
+}
+
+/// Download all the content in a namespace that matches the filters, along with
+/// a manifest of its metadata
+#[derive(Debug, Parser, Command)]
+pub struct Export {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
+    #[clap(from_global)]
+    pub namespace: String,
+
+    #[clap(flatten)]
+    pub filter: Filter,
+
+    /// Directory to write the content and manifest to
+    #[clap(short = 'd', long, default_value = ".")]
+    pub output_dir: PathBuf,
+
+    /// Path of each piece of content inside the output directory. Available
+    /// fields are {id}, {name}, {graph}, {mime_type}, {source}, {parent_id}
+    /// and {namespace}. {graph} is the --graph filter, or the first graph the
+    /// content belongs to
+    #[clap(long, default_value = "{graph}/{id}-{name}")]
+    pub template: String,
+
+    /// Number of downloads to run at the same time
+    #[clap(long, default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..))]
+    pub parallel: u16,
+}
+
+/// Metadata of the exported content, written to `manifest.json`.
+#[derive(Debug, Serialize)]
+pub struct ManifestEntry {
+    /// Relative to the manifest.
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub content: api::ContentMetadata,
+}
+
+impl Export {
+    fn path(&self, content: &api::ContentMetadata) -> PathBuf {
+        export_path(&self.template, self.filter.graph.as_deref(), content)
+    }
+
+    async fn download(
+        &self,
+        client: &client::Client,
+        progress: &Progress,
+        content: api::ContentMetadata,
+        path: PathBuf,
+    ) -> TransferResult {
+        let pb = progress.start(&path.display().to_string());
+        pb.set_length(content.size.0);
+
+        let dest = self.output_dir.join(&path);
+        let result = match dest.parent() {
+            Some(dir) => tokio::fs::create_dir_all(dir).await.map_err(Into::into),
+            None => Ok(()),
+        };
+        let result = match result {
+            Ok(()) => save(client, &content, &dest, false, &pb).await,
+            Err(e) => Err(e),
+        };
+
+        progress.finish(pb);
+
+        match result {
+            Ok(()) => TransferResult {
+                path: dest.display().to_string(),
+                content_id: content.id.clone(),
+                status: "downloaded",
+                error: String::new(),
+            },
+            Err(e) => TransferResult {
+                path: dest.display().to_string(),
+                content_id: content.id.clone(),
+                status: "failed",
+                error: format!("{:#}", e),
+            },
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Command for Export {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let client = self.api_server.clone().with_namespace(&self.namespace);
+
+        let content = client
+            .list_all::<Vec<api::ContentMetadata>>(api::ListContent {
+                limit: Some(client::PAGE_SIZE),
+                ..self.filter.query()
+            })
+            .await?;
+
+        let manifest: Vec<ManifestEntry> = content
+            .into_iter()
+            .filter(|c| self.filter.matches(c))
+            .map(|c| ManifestEntry {
+                path: self.path(&c),
+                content: c,
+            })
+            .collect();
+
+        if manifest.is_empty() {
+            return Err(eyre!("no content matched"));
+        }
+
+        // Concurrent downloads to the same path would overwrite each other.
+        let mut paths: HashMap<&Path, &str> = HashMap::new();
+        for entry in &manifest {
+            if let Some(other) = paths.insert(&entry.path, &entry.content.id) {
+                return Err(eyre!(
+                    "{} and {} would both be exported to {}, add {{id}} to --template",
+                    other,
+                    entry.content.id,
+                    entry.path.display()
+                ));
+            }
+        }
+
+        info!(files = manifest.len(), dir = ?self.output_dir, "exporting");
+
+        let progress = Progress::new(self.output.progress(), manifest.len(), "downloaded")?;
+
+        let downloads = manifest
+            .iter()
+            .map(|entry| (entry.content.clone(), entry.path.clone()))
+            .collect::<Vec<_>>();
+
+        let mut results: Vec<TransferResult> = stream::iter(downloads)
+            .map(|(content, path)| self.download(&client, &progress, content, path))
+            .buffer_unordered(self.parallel as usize)
+            .collect()
+            .await;
+
+        progress.clear()?;
+
+        // Only what made it to disk can be imported again.
+        let manifest: Vec<&ManifestEntry> = manifest
+            .iter()
+            .filter(|entry| {
+                results
+                    .iter()
+                    .any(|r| r.content_id == entry.content.id && r.error.is_empty())
+            })
+            .collect();
+
+        tokio::fs::create_dir_all(&self.output_dir).await?;
+        tokio::fs::write(
+            self.output_dir.join("manifest.json"),
+            serde_json::to_string_pretty(&manifest)?,
+        )
+        .await?;
+
+        results.sort_by(|a, b| a.path.cmp(&b.path));
+
+        self.output.list(&results)?;
+
+        failures(&results, "downloads")
+    }
+}
+
+// Values come from the server, keep them from escaping the output directory.
+fn export_path(template: &str, graph: Option<&str>, content: &api::ContentMetadata) -> PathBuf {
+    let graph = match graph {
+        Some(graph) => graph,
+        None => content
+            .extraction_graph_names
+            .first()
+            .map(String::as_str)
+            .unwrap_or("_"),
+    };
+
+    let fields = [
+        ("{id}", content.id.as_str()),
+        ("{name}", content.name.as_str()),
+        ("{graph}", graph),
+        ("{mime_type}", content.mime_type.as_str()),
+        ("{source}", content.source.as_str()),
+        ("{parent_id}", content.parent_id.as_str()),
+        ("{namespace}", content.namespace.as_str()),
+    ];
+
+    let path = fields
+        .iter()
+        .fold(template.to_string(), |path, (field, value)| {
+            let value = match value.replace(['/', '\\'], "_") {
+                v if v.is_empty() || v == "." || v == ".." => "_".to_string(),
+                v => v,
+            };
+
+            path.replace(field, &value)
+        });
+
+    Path::new(&path)
+        .components()
+        .filter(|c| matches!(c, std::path::Component::Normal(_)))
+        .collect()
+}
+
+/// Show every field of a piece of content
+#[derive(Debug, Parser, Command)]
+pub struct Describe {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
+    #[clap(from_global)]
+    pub namespace: String,
+
+    /// ID of the content
+    pub id: String,
+}
+
+#[async_trait::async_trait]
+impl Command for Describe {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let content: api::ContentMetadata = self
             .api_server
             .clone()
             .with_namespace(&self.namespace)
-            .get_stream::<client::types::Download>(Some(&self.id))
+            .get(&self.id)
             .await?;
 
-        let progress = match size {
-            Some(size) => self.progress_bar(size)?,
-            None => self.spinner()?,
-        };
-
-        match &self.file {
-            Some(path) => self.to_file(path, progress.wrap_async_read(reader)).await,
-            None => self.to_stdout(reader).await,
-        }
+        self.output.describe(&content)
     }
 }
 
 /// Get the details of a piece of content
 #[derive(Debug, Parser, Command)]
 pub struct Get {
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub api_server: client::Client,
 
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub output: output::Format,
 
     #[clap(from_global)]
@@ -185,70 +591,1610 @@ impl Command for Get {
 
         self.output.item(&content)
     }
+
+    fn watchable(&self) -> bool {
+        true
+    }
 }
 
-/// List all the content in a namespace
+/// View and change the labels on a piece of content
 #[derive(Debug, Parser, Command)]
-pub struct List {
-    #[clap(from_global)]
+pub struct Labels {
+    #[command(subcommand)]
+    pub cmd: LabelsCmd,
+}
+
+#[derive(Debug, Subcommand, Command)]
+pub enum LabelsCmd {
+    Get(GetLabels),
+    Remove(RemoveLabels),
+    Set(SetLabels),
+}
+
+impl Command for Labels {}
+
+#[derive(Debug, Serialize, Tabled, Columns)]
+pub struct Label {
+    pub key: String,
+    pub value: String,
+}
+
+fn print_labels(
+    output: &output::Format,
+    labels: &HashMap<String, serde_json::Value>,
+) -> Result<()> {
+    if let output::Kind::Json | output::Kind::Yaml = output.kind {
+        return output.document(labels);
+    }
+
+    let mut rows: Vec<Label> = labels
+        .iter()
+        .map(|(key, value)| Label {
+            key: key.clone(),
+            value: match value {
+                serde_json::Value::String(value) => value.clone(),
+                value => value.to_string(),
+            },
+        })
+        .collect();
+    rows.sort_by(|a, b| a.key.cmp(&b.key));
+
+    output.list(&rows)
+}
+
+// The server replaces all the labels at once, changes are applied to the
+// current set.
+async fn update_labels<F>(
+    client: &client::Client,
+    namespace: &str,
+    id: &str,
+    change: F,
+) -> Result<HashMap<String, serde_json::Value>>
+where
+    F: FnOnce(&mut HashMap<String, serde_json::Value>),
+{
+    let client = client.clone().with_namespace(namespace);
+
+    let content: api::ContentMetadata = client.get(id).await?;
+    let mut labels = content.labels;
+    change(&mut labels);
+
+    client
+        .update(
+            id,
+            &api::UpdateLabelsRequest {
+                labels: labels.clone(),
+            },
+        )
+        .await?;
+
+    Ok(labels)
+}
+
+/// Show the labels on a piece of content
+#[derive(Debug, Parser, Command)]
+pub struct GetLabels {
+    #[clap(flatten)]
     pub api_server: client::Client,
 
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub output: output::Format,
 
     #[clap(from_global)]
     pub namespace: String,
+
+    /// ID of the content
+    pub id: String,
 }
 
 #[async_trait::async_trait]
-impl Command for List {
+impl Command for GetLabels {
     #[instrument]
     async fn run(&self) -> Result<()> {
-        let content: Vec<api::ContentMetadata> = self
+        let content: api::ContentMetadata = self
             .api_server
             .clone()
             .with_namespace(&self.namespace)
-            .list()
+            .get(&self.id)
             .await?;
 
-        self.output.list(&content)
+        print_labels(&self.output, &content.labels)
+    }
+
+    fn watchable(&self) -> bool {
+        true
     }
 }
 
-/// Upload a piece of content
+/// Remove labels from a piece of content
 #[derive(Debug, Parser, Command)]
-pub struct Upload {
+pub struct RemoveLabels {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
     #[clap(from_global)]
+    pub namespace: String,
+
+    /// ID of the content
+    pub id: String,
+
+    /// Keys of the labels to remove
+    #[clap(required = true)]
+    pub keys: Vec<String>,
+}
+
+#[async_trait::async_trait]
+impl Command for RemoveLabels {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let labels = update_labels(&self.api_server, &self.namespace, &self.id, |labels| {
+            for key in &self.keys {
+                labels.remove(key);
+            }
+        })
+        .await?;
+
+        print_labels(&self.output, &labels)
+    }
+}
+
+/// Add or change labels on a piece of content, other labels are kept
+#[derive(Debug, Parser, Command)]
+pub struct SetLabels {
+    #[clap(flatten)]
     pub api_server: client::Client,
 
+    #[clap(flatten)]
+    pub output: output::Format,
+
     #[clap(from_global)]
+    pub namespace: String,
+
+    /// ID of the content
+    pub id: String,
+
+    /// Labels to set, as key:value
+    #[clap(required = true, value_name = "KEY:VALUE", value_parser = parse_label)]
+    pub labels: Vec<(String, String)>,
+}
+
+#[async_trait::async_trait]
+impl Command for SetLabels {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let labels = update_labels(&self.api_server, &self.namespace, &self.id, |labels| {
+            for (key, value) in &self.labels {
+                labels.insert(key.clone(), label_value(value));
+            }
+        })
+        .await?;
+
+        print_labels(&self.output, &labels)
+    }
+}
+
+/// List the content in a namespace, a page at a time unless --all is passed
+#[derive(Debug, Parser, Command)]
+pub struct List {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
     pub output: output::Format,
 
     #[clap(from_global)]
     pub namespace: String,
 
-    /// Path to get the content from
-    pub path: clio::InputPath,
+    #[clap(flatten)]
+    pub filter: Filter,
 
-    /// Name of the graph the content is associated with
-    #[clap(short, long, required = true, num_args(1..))]
-    pub graph: Vec<String>,
+    /// Maximum number of items to return, the page size with --all
+    #[clap(long)]
+    pub limit: Option<u64>,
+
+    /// Start listing after this content ID
+    #[clap(long)]
+    pub start_id: Option<String>,
+
+    /// Fetch every page
+    #[clap(long)]
+    pub all: bool,
 }
 
 #[async_trait::async_trait]
-impl Command for Upload {
+impl Command for List {
     #[instrument]
     async fn run(&self) -> Result<()> {
-        info!("names: {:?}", self.graph);
-        let content = client::types::ContentUpload::new(self.path.path().path())
-            .with_graph_names(&self.graph);
+        let client = self.api_server.clone().with_namespace(&self.namespace);
 
-        self.api_server
-            .clone()
-            .with_namespace(&self.namespace)
-            .upload(content)
+        let query = api::ListContent {
+            start_id: self.start_id.clone(),
+            limit: client::page_limit(self.limit, self.all),
+            return_total: true,
+            ..self.filter.query()
+        };
+
+        if self.all {
+            let content = client
+                .list_all::<Vec<api::ContentMetadata>>(query)
+                .await?
+                .into_iter()
+                .filter(|c| self.filter.matches(c))
+                .collect::<Vec<_>>();
+
+            return self.output.list(&content);
+        }
+
+        let page = client
+            .list_page::<Vec<api::ContentMetadata>>(&query)
             .await?;
+        let content = page
+            .items
+            .into_iter()
+            .filter(|c| self.filter.matches(c))
+            .collect::<Vec<_>>();
+
+        self.output.list(&content)?;
+
+        self.output.next_page(
+            content.len(),
+            page.total,
+            page.next.and_then(|q| q.start_id),
+        );
 
         Ok(())
     }
+
+    fn watchable(&self) -> bool {
+        true
+    }
+}
+
+/// Show a piece of content along with everything that was extracted from it,
+/// starting at the content it was originally derived from
+#[derive(Debug, Parser, Command)]
+pub struct Tree {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
+    #[clap(from_global)]
+    pub namespace: String,
+
+    /// ID of the content, any piece of content in the tree works
+    pub id: String,
+}
+
+/// Content with its descendants nested under it, the JSON output of `content
+/// tree`.
+#[derive(Debug, Serialize)]
+pub struct ContentTree {
+    #[serde(flatten)]
+    pub content: api::ContentMetadata,
+    pub children: Vec<ContentTree>,
+}
+
+#[derive(Debug, Serialize, Tabled, Columns)]
+pub struct TreeRow {
+    pub id: String,
+    pub name: String,
+    pub mime_type: String,
+    /// The extraction policy that produced the content, or `ingestion`.
+    pub source: String,
+}
+
+impl ContentTree {
+    // Children are keyed by their parent ID. IDs that have already been seen
+    // are skipped so a bad parent_id can't recurse forever.
+    fn build(
+        content: api::ContentMetadata,
+        children: &mut HashMap<String, Vec<api::ContentMetadata>>,
+    ) -> Self {
+        let mut kids = children.remove(&content.id).unwrap_or_default();
+        kids.sort_by(|a, b| a.id.cmp(&b.id));
+
+        Self {
+            children: kids
+                .into_iter()
+                .map(|child| Self::build(child, children))
+                .collect(),
+            content,
+        }
+    }
+
+    fn rows(&self, prefix: &str, last: bool, root: bool, rows: &mut Vec<TreeRow>) {
+        let (branch, indent) = match (root, last) {
+            (true, _) => ("", ""),
+            (false, true) => ("└── ", "    "),
+            (false, false) => ("├── ", "│   "),
+        };
+
+        rows.push(TreeRow {
+            id: format!("{}{}{}", prefix, branch, self.content.id),
+            name: self.content.name.clone(),
+            mime_type: self.content.mime_type.clone(),
+            source: self.content.source.clone(),
+        });
+
+        let prefix = format!("{}{}", prefix, indent);
+        for (i, child) in self.children.iter().enumerate() {
+            child.rows(&prefix, i == self.children.len() - 1, false, rows);
+        }
+    }
+}
+
+// Walks down a level at a time, fetching the children of every piece of
+// content on the level concurrently.
+async fn descendants(
+    client: &client::Client,
+    root: &str,
+) -> Result<HashMap<String, Vec<api::ContentMetadata>>> {
+    let mut children = HashMap::new();
+    let mut seen = HashSet::from([root.to_string()]);
+    let mut level = vec![root.to_string()];
+
+    while !level.is_empty() {
+        let pages: Vec<(String, Vec<api::ContentMetadata>)> = stream::iter(level)
+            .map(|id| async move {
+                let query = api::ListContent {
+                    parent_id: Some(id.clone()),
+                    limit: Some(client::PAGE_SIZE),
+                    ..Default::default()
+                };
+
+                client
+                    .list_all::<Vec<api::ContentMetadata>>(query)
+                    .await
+                    .map(|kids| (id, kids))
+            })
+            .buffer_unordered(8)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<_>>()?;
+
+        level = vec![];
+        for (id, kids) in pages {
+            // Servers that ignore the parent_id filter send everything.
+            let kids: Vec<_> = kids
+                .into_iter()
+                .filter(|kid| kid.parent_id == id && seen.insert(kid.id.clone()))
+                .collect();
+
+            level.extend(kids.iter().map(|kid| kid.id.clone()));
+            children.insert(id, kids);
+        }
+    }
+
+    Ok(children)
+}
+
+#[async_trait::async_trait]
+impl Command for Tree {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let client = self.api_server.clone().with_namespace(&self.namespace);
+
+        let content: api::ContentMetadata = client.get(&self.id).await?;
+        let root = match content.root_content_id.as_str() {
+            "" => content,
+            id if id == content.id => content,
+            id => client.get(id).await?,
+        };
+
+        let mut children = descendants(&client, &root.id).await?;
+        let tree = ContentTree::build(root, &mut children);
+
+        match self.output.kind {
+            output::Kind::Json | output::Kind::Yaml => self.output.document(&tree)?,
+            _ => {
+                let mut rows = vec![];
+                tree.rows("", true, true, &mut rows);
+
+                self.output.list(&rows)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Upload content from files, directories or stdin
+#[derive(Debug, Parser, Command)]
+pub struct Upload {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
+    #[clap(from_global)]
+    pub namespace: String,
+
+    /// Files or directories to upload, directories are walked recursively. Use
+    /// `-` to read from stdin
+    #[clap(required = true, value_parser = clap::value_parser!(clio::ClioPath).exists())]
+    pub paths: Vec<clio::ClioPath>,
+
+    /// Name of the graph the content is associated with, can be repeated or
+    /// comma separated
+    #[clap(short, long, required = true, num_args(1..), value_delimiter = ',')]
+    pub graph: Vec<String>,
+
+    /// Only upload files matching this glob, can be repeated
+    #[clap(long)]
+    pub include: Vec<String>,
+
+    /// Skip files matching this glob, can be repeated
+    #[clap(long)]
+    pub exclude: Vec<String>,
+
+    /// Name of the content when reading from stdin, or of the file when
+    /// uploading a single one
+    #[clap(long)]
+    pub name: Option<String>,
+
+    /// MIME type of the content, guessed from the name by default
+    #[clap(long)]
+    pub mime_type: Option<String>,
+
+    /// Label to attach to the content, as key:value, can be repeated
+    #[clap(long = "label", value_name = "KEY:VALUE", value_parser = parse_label)]
+    pub labels: Vec<(String, String)>,
+
+    /// Number of uploads to run at the same time
+    #[clap(long, default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..))]
+    pub parallel: u16,
+
+    /// Retry the upload on transient errors, only use this when uploading the
+    /// same content twice is safe
+    #[clap(long)]
+    pub idempotent: bool,
+
+    /// Wait until the graphs are done extracting from the uploads, exits the
+    /// same way `content wait` does
+    #[clap(long)]
+    pub wait: bool,
+
+    /// Give up waiting after this long, waits forever by default
+    #[clap(long, requires = "wait", value_parser = humantime::parse_duration)]
+    pub timeout: Option<Duration>,
+}
+
+enum Source {
+    File(PathBuf),
+    Stdin,
+}
+
+/// Filters for the content in a namespace
+#[derive(Debug, Default, Clone, clap::Args)]
+pub struct Filter {
+    /// Only content in this graph
+    #[clap(long)]
+    pub graph: Option<String>,
+
+    /// Only content with this MIME type
+    #[clap(long)]
+    pub mime_type: Option<String>,
+
+    /// Only content from this source, such as `ingestion` or the name of an
+    /// extraction policy
+    #[clap(long)]
+    pub source: Option<String>,
+
+    /// Only content derived from this piece of content
+    #[clap(long)]
+    pub parent_id: Option<String>,
+
+    /// Only content with these labels, as key:value, can be repeated
+    #[clap(
+        long = "labels-eq",
+        value_name = "KEY:VALUE",
+        value_delimiter = ',',
+        value_parser = parse_label
+    )]
+    pub labels: Vec<(String, String)>,
+
+    /// Only content created at or after this time, as an RFC 3339 time,
+    /// seconds since the epoch or a duration such as 3h for that long ago
+    #[clap(long, value_name = "TIME")]
+    pub since: Option<output::tabled::Timestamp>,
+
+    /// Only content created before this time, in the same formats as --since
+    #[clap(long, value_name = "TIME")]
+    pub before: Option<output::tabled::Timestamp>,
+}
+
+impl Filter {
+    /// The filters the server supports, everything is also checked locally by
+    /// `matches`.
+    pub fn query(&self) -> api::ListContent {
+        api::ListContent {
+            graph: self.graph.clone(),
+            source: self.source.clone(),
+            parent_id: self.parent_id.clone(),
+            labels_eq: match self.labels.is_empty() {
+                true => None,
+                false => Some(
+                    self.labels
+                        .iter()
+                        .map(|(key, value)| (key.clone(), label_value(value)))
+                        .collect(),
+                ),
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn matches(&self, content: &api::ContentMetadata) -> bool {
+        let eq = |filter: &Option<String>, value: &str| filter.as_ref().is_none_or(|f| f == value);
+
+        self.graph
+            .as_ref()
+            .is_none_or(|g| content.extraction_graph_names.contains(g))
+            && eq(&self.mime_type, &content.mime_type)
+            && eq(&self.source, &content.source)
+            && eq(&self.parent_id, &content.parent_id)
+            && self.since.is_none_or(|t| content.created_at >= t)
+            && self.before.is_none_or(|t| content.created_at < t)
+            && self.labels.iter().all(|(key, value)| {
+                content
+                    .labels
+                    .get(key)
+                    .is_some_and(|v| *v == label_value(value) || v.as_str() == Some(value))
+            })
+    }
+}
+
+// Values are sent the same way the server parses them, as JSON when they parse
+// as such and as strings otherwise.
+fn label_value(raw: &str) -> serde_json::Value {
+    serde_json::from_str(raw).unwrap_or_else(|_| serde_json::Value::String(raw.to_string()))
+}
+
+fn parse_label(raw: &str) -> Result<(String, String)> {
+    let (key, value) = api::utils::parse_validate_label_raw(raw)?;
+    api::utils::validate_label_key(&key)?;
+    api::utils::validate_label_value(&value)?;
+
+    Ok((key, value))
+}
+
+// A bar per upload in flight, with a count of finished files above them when
+// there's more than one.
+struct Progress {
+    bars: MultiProgress,
+    style: ProgressStyle,
+    total: Option<ProgressBar>,
+}
+
+impl Progress {
+    fn new(enabled: bool, files: usize, verb: &str) -> Result<Self> {
+        let bars = MultiProgress::with_draw_target(match enabled {
+            true => ProgressDrawTarget::stderr(),
+            false => ProgressDrawTarget::hidden(),
+        });
+
+        let total = match files {
+            1 => None,
+            _ => Some(bars.add(ProgressBar::new(files as u64)).with_style(
+                ProgressStyle::default_bar().template(&format!(
+                    "[{{elapsed_precise}}] {{pos}}/{{len}} files {}",
+                    verb
+                ))?,
+            )),
+        };
+
+        if let Some(total) = &total {
+            total.tick();
+        }
+
+        Ok(Self {
+            bars,
+            style: ProgressStyle::default_bar()
+                .template(
+                    "{spinner:.green} [{bar:40.cyan/blue}] {bytes}/{total_bytes} \
+                     ({eta}) {wide_msg}",
+                )?
+                .progress_chars("#>-"),
+            total,
+        })
+    }
+
+    fn start(&self, path: &str) -> ProgressBar {
+        self.bars.add(
+            ProgressBar::new(0)
+                .with_style(self.style.clone())
+                .with_message(path.to_string()),
+        )
+    }
+
+    fn finish(&self, pb: ProgressBar) {
+        pb.finish_and_clear();
+        self.bars.remove(&pb);
+
+        if let Some(total) = &self.total {
+            total.inc(1);
+        }
+    }
+
+    fn clear(&self) -> Result<()> {
+        if let Some(total) = &self.total {
+            total.finish_and_clear();
+        }
+
+        Ok(self.bars.clear()?)
+    }
+}
+
+#[derive(Debug, Serialize, Tabled, Columns)]
+pub struct TransferResult {
+    pub path: String,
+    pub content_id: String,
+    pub status: &'static str,
+    pub error: String,
+}
+
+impl Upload {
+    fn globs(patterns: &[String]) -> Result<Option<GlobSet>> {
+        if patterns.is_empty() {
+            return Ok(None);
+        }
+
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Glob::new(pattern)?);
+        }
+
+        Ok(Some(builder.build()?))
+    }
+
+    // Globs are matched against the path relative to the argument it was found
+    // through, for files passed directly that is the file name.
+    fn sources(&self) -> Result<Vec<Source>> {
+        let include = Self::globs(&self.include)?;
+        let exclude = Self::globs(&self.exclude)?;
+
+        let mut sources = vec![];
+        for path in &self.paths {
+            if path.is_std() {
+                sources.push(Source::Stdin);
+                continue;
+            }
+
+            let root = path.path();
+            let files = if root.is_dir() {
+                walk(root, &mut HashSet::new())?
+            } else {
+                vec![root.to_path_buf()]
+            };
+
+            for file in files {
+                let rel = match file.strip_prefix(root) {
+                    Ok(rel) if !rel.as_os_str().is_empty() => rel,
+                    _ => Path::new(file.file_name().unwrap_or_default()),
+                };
+
+                if include.as_ref().is_some_and(|g| !g.is_match(rel))
+                    || exclude.as_ref().is_some_and(|g| g.is_match(rel))
+                {
+                    continue;
+                }
+
+                sources.push(Source::File(file));
+            }
+        }
+
+        Ok(sources)
+    }
+
+    // `name` is only given for the one source `--name` applies to.
+    async fn upload(
+        &self,
+        client: &client::Client,
+        progress: &Progress,
+        source: Source,
+        name: Option<&str>,
+    ) -> TransferResult {
+        let (path, content) = match &source {
+            Source::File(path) => (
+                path.display().to_string(),
+                client::types::ContentUpload::new(path),
+            ),
+            Source::Stdin => (
+                "-".to_string(),
+                client::types::ContentUpload::from_reader(
+                    name.unwrap_or_default(),
+                    tokio::io::stdin(),
+                ),
+            ),
+        };
+
+        let pb = progress.start(&path);
+
+        let mut content = content
+            .with_graph_names(&self.graph)
+            .with_labels(
+                self.labels
+                    .iter()
+                    .map(|(key, value)| (key.clone(), label_value(value)))
+                    .collect(),
+            )
+            .with_progress(pb.clone());
+        if let Some(name) = name {
+            content = content.with_name(name);
+        }
+        if let Some(mime_type) = &self.mime_type {
+            content = content.with_mime_type(mime_type);
+        }
+
+        let result = client.upload(content).await;
+        progress.finish(pb);
+
+        match result {
+            Ok(resp) => TransferResult {
+                path,
+                content_id: resp.content_id,
+                status: "uploaded",
+                error: String::new(),
+            },
+            Err(e) => TransferResult {
+                path,
+                content_id: String::new(),
+                status: "failed",
+                error: format!("{:#}", e),
+            },
+        }
+    }
+}
+
+// Symlinked directories are followed, but each directory is only walked once
+// so a symlink cycle can't recurse forever.
+fn walk(dir: &Path, seen: &mut HashSet<PathBuf>) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+
+    if !seen.insert(dir.canonicalize()?) {
+        return Ok(files);
+    }
+
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            files.extend(walk(&path, seen)?);
+        } else {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+
+    Ok(files)
+}
+
+#[async_trait::async_trait]
+impl Command for Upload {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        if self.paths.iter().any(|p| p.is_std()) && self.name.is_none() {
+            return Err(eyre!("--name is required when reading from stdin"));
+        }
+
+        let mut client = self.api_server.clone().with_namespace(&self.namespace);
+        if self.idempotent {
+            client = client.idempotent();
+        }
+
+        let sources = self.sources()?;
+        if sources.is_empty() {
+            return Err(eyre!("no files matched"));
+        }
+
+        let stdin = sources
+            .iter()
+            .filter(|s| matches!(s, Source::Stdin))
+            .count();
+        if stdin > 1 {
+            return Err(eyre!("stdin can only be uploaded once"));
+        }
+
+        // --name is for stdin when it's read, otherwise for a lone file.
+        if self.name.is_some() && stdin == 0 && sources.len() > 1 {
+            return Err(eyre!(
+                "--name can only be used with stdin or a single file, {} files matched",
+                sources.len()
+            ));
+        }
+
+        let name = |source: &Source| match source {
+            Source::Stdin => self.name.as_deref(),
+            Source::File(_) if stdin == 0 => self.name.as_deref(),
+            Source::File(_) => None,
+        };
+
+        info!(files = sources.len(), graphs = ?self.graph, "uploading");
+
+        let progress = Progress::new(self.output.progress(), sources.len(), "uploaded")?;
+
+        let mut results: Vec<TransferResult> = stream::iter(sources)
+            .map(|source| {
+                let name = name(&source);
+
+                self.upload(&client, &progress, source, name)
+            })
+            .buffer_unordered(self.parallel as usize)
+            .collect()
+            .await;
+
+        progress.clear()?;
+
+        results.sort_by(|a, b| a.path.cmp(&b.path));
+
+        if !self.wait {
+            self.output.list(&results)?;
+
+            return failures(&results, "uploads");
+        }
+
+        let uploaded = failures(&results, "uploads");
+        let waited = |r: &&mut TransferResult| r.error.is_empty() && !r.content_id.is_empty();
+        let ids: Vec<_> = results
+            .iter_mut()
+            .filter(waited)
+            .map(|r| r.content_id.clone())
+            .collect();
+
+        let extractions = wait(&client, &ids, self.timeout, self.output.progress()).await?;
+
+        for (result, extraction) in results.iter_mut().filter(waited).zip(&extractions) {
+            result.status = match extraction.status {
+                "failed" => "extraction failed",
+                status => status,
+            };
+            result.error = extraction.error.clone();
+        }
+
+        self.output.list(&results)?;
+
+        uploaded?;
+
+        extraction_exit(extractions.iter().map(|e| e.status))
+    }
+}
+
+fn failures(results: &[TransferResult], what: &str) -> Result<()> {
+    match results.iter().filter(|r| r.error.is_empty()).count() {
+        ok if ok == results.len() => Ok(()),
+        ok => Err(eyre!(
+            "{} of {} {} failed",
+            results.len() - ok,
+            results.len(),
+            what
+        )),
+    }
+}
+
+/// Exit code of `wait` when an extraction failed.
+pub const EXIT_FAILED: i32 = 3;
+/// Exit code of `wait` when extraction didn't finish in time, the same as
+/// timeout(1) uses.
+pub const EXIT_TIMEOUT: i32 = 124;
+
+// How often `wait` polls the server.
+const WAIT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Wait until the extraction graphs are done with content and everything
+/// derived from it. Exits with 3 when an extraction failed and 124 when the
+/// timeout elapsed, content that no tasks were scheduled for by then is shown
+/// as `no tasks`
+#[derive(Debug, Parser, Command)]
+pub struct Wait {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
+    #[clap(from_global)]
+    pub namespace: String,
+
+    /// IDs of the content to wait for
+    #[clap(required = true)]
+    pub ids: Vec<String>,
+
+    /// Give up after this long, waits forever by default
+    #[clap(long, value_parser = humantime::parse_duration)]
+    pub timeout: Option<Duration>,
+}
+
+#[derive(Debug, Serialize, Tabled, Columns)]
+pub struct ExtractionResult {
+    pub content_id: String,
+    pub status: &'static str,
+    /// Extractions that hadn't finished when waiting stopped.
+    pub pending: usize,
+    pub error: String,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Extraction {
+    pending: usize,
+    /// Content in a graph that no tasks have been scheduled for yet.
+    unscheduled: usize,
+    failed: Vec<String>,
+}
+
+impl Extraction {
+    // Tasks are created some time after the content they extract from, and
+    // not at all when no policy applies to it. There's no telling the two
+    // apart, so content in a graph without tasks is waited for until the
+    // timeout.
+    fn of(content: &api::ContentMetadata, tasks: &[api::Task]) -> Self {
+        if tasks.is_empty() {
+            return Self {
+                unscheduled: usize::from(!content.extraction_graph_names.is_empty()),
+                ..Default::default()
+            };
+        }
+
+        Self {
+            pending: tasks
+                .iter()
+                .filter(|task| task.outcome == api::TaskOutcome::Unknown)
+                .count(),
+            unscheduled: 0,
+            failed: tasks
+                .iter()
+                .filter(|task| task.outcome == api::TaskOutcome::Failed)
+                .map(|task| format!("{} on {}", task.extraction_policy_id, content.id))
+                .collect(),
+        }
+    }
+
+    fn done(&self) -> bool {
+        self.pending == 0 && self.unscheduled == 0
+    }
+
+    // Failures take precedence, then what is still running.
+    fn status(&self) -> &'static str {
+        match self {
+            e if !e.failed.is_empty() => "failed",
+            e if e.pending > 0 => "timed out",
+            e if e.unscheduled > 0 => "no tasks",
+            _ => "extracted",
+        }
+    }
+}
+
+// The tasks of the content and everything derived from it.
+async fn extraction(client: &client::Client, id: &str) -> Result<Extraction> {
+    let content: api::ContentMetadata = client.get(id).await?;
+    let children = descendants(client, &content.id).await?;
+
+    let mut extraction = Extraction::default();
+    for content in std::iter::once(content).chain(children.into_values().flatten()) {
+        if content.extraction_graph_names.is_empty() {
+            continue;
+        }
+
+        let query = api::ListTasks {
+            content_id: Some(content.id.clone()),
+            limit: Some(client::PAGE_SIZE),
+            ..Default::default()
+        };
+        let tasks = client.list_all::<Vec<api::Task>>(query).await?;
+
+        let Extraction {
+            pending,
+            unscheduled,
+            failed,
+        } = Extraction::of(&content, &tasks);
+        extraction.pending += pending;
+        extraction.unscheduled += unscheduled;
+        extraction.failed.extend(failed);
+    }
+
+    Ok(extraction)
+}
+
+// Polls until nothing is pending or the timeout elapses. Content that couldn't
+// be polled is tried again, it times out with the error if it never works.
+async fn wait(
+    client: &client::Client,
+    ids: &[String],
+    timeout: Option<Duration>,
+    progress: bool,
+) -> Result<Vec<ExtractionResult>> {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+    let spinner = match progress {
+        true => ProgressBar::new_spinner(),
+        false => ProgressBar::hidden(),
+    };
+    spinner.set_style(
+        ProgressStyle::default_spinner().template("{spinner:.green} [{elapsed_precise}] {msg}")?,
+    );
+    spinner.enable_steady_tick(Duration::from_millis(100));
+
+    loop {
+        let mut extractions = vec![];
+        for id in ids {
+            let extraction = extraction(client, id).await;
+
+            // The server can be briefly unavailable, the next poll may work.
+            if let Err(e) = &extraction {
+                spinner.suspend(|| eprintln!("warning: {:#}", e));
+            }
+
+            extractions.push(extraction);
+        }
+
+        let pending: usize = extractions
+            .iter()
+            .flatten()
+            .map(|e| e.pending + e.unscheduled)
+            .sum();
+        spinner.set_message(format!("waiting for {} extractions", pending));
+
+        let remaining = match deadline {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => WAIT_INTERVAL,
+        };
+
+        let done = extractions
+            .iter()
+            .all(|e| e.as_ref().is_ok_and(Extraction::done));
+        if done || remaining.is_zero() {
+            spinner.finish_and_clear();
+
+            return Ok(ids
+                .iter()
+                .zip(extractions)
+                .map(|(id, extraction)| match extraction {
+                    Ok(extraction) => ExtractionResult {
+                        content_id: id.clone(),
+                        status: extraction.status(),
+                        pending: extraction.pending,
+                        error: extraction.failed.join(", "),
+                    },
+                    Err(e) => ExtractionResult {
+                        content_id: id.clone(),
+                        status: "timed out",
+                        pending: 0,
+                        error: format!("{:#}", e),
+                    },
+                })
+                .collect());
+        }
+
+        tokio::time::sleep(remaining.min(WAIT_INTERVAL)).await;
+    }
+}
+
+// Failures take precedence, the outcome of content that timed out isn't known.
+fn extraction_exit(statuses: impl IntoIterator<Item = &'static str>) -> Result<()> {
+    let statuses: Vec<_> = statuses.into_iter().collect();
+
+    if statuses.contains(&"failed") {
+        return Err(command::Exit(EXIT_FAILED).into());
+    }
+
+    if statuses.contains(&"timed out") || statuses.contains(&"no tasks") {
+        return Err(command::Exit(EXIT_TIMEOUT).into());
+    }
+
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl Command for Wait {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let client = self.api_server.clone().with_namespace(&self.namespace);
+
+        let results = wait(&client, &self.ids, self.timeout, self.output.progress()).await?;
+
+        self.output.list(&results)?;
+
+        extraction_exit(results.iter().map(|r| r.status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::tabled::Bytes;
+
+    fn content() -> api::ContentMetadata {
+        api::ContentMetadata {
+            id: "abc".to_string(),
+            name: "report.pdf".to_string(),
+            namespace: "default".to_string(),
+            mime_type: "application/pdf".to_string(),
+            extraction_graph_names: vec!["summarize".to_string(), "embed".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn task(policy: &str, outcome: api::TaskOutcome) -> api::Task {
+        api::Task {
+            id: format!("{}-task", policy),
+            extractor: "tensorlake/minilm-l6".to_string(),
+            extraction_policy_id: policy.to_string(),
+            extraction_graph_name: "summarize".to_string(),
+            output_index_table_mapping: HashMap::new(),
+            namespace: "default".to_string(),
+            content_metadata: content(),
+            input_params: serde_json::Value::Null,
+            outcome,
+            index_tables: vec![],
+        }
+    }
+
+    #[test]
+    fn labels_from_flags() {
+        assert_eq!(
+            parse_label("topic:news").unwrap(),
+            ("topic".to_string(), "news".to_string())
+        );
+        assert_eq!(
+            parse_label("topic:").unwrap(),
+            ("topic".to_string(), "".to_string())
+        );
+
+        for raw in ["topic", "topic:a:b", ":news", "-topic:news", "topic:news!"] {
+            assert!(parse_label(raw).is_err(), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn label_values_as_the_server_parses_them() {
+        assert_eq!(label_value("1"), serde_json::json!(1));
+        assert_eq!(label_value("true"), serde_json::json!(true));
+        assert_eq!(label_value("news"), serde_json::json!("news"));
+        assert_eq!(label_value(""), serde_json::json!(""));
+    }
+
+    #[test]
+    fn filter_matches() {
+        let content = api::ContentMetadata {
+            labels: HashMap::from([
+                ("year".to_string(), serde_json::json!(2024)),
+                ("topic".to_string(), serde_json::json!("news")),
+                ("page".to_string(), serde_json::json!("3")),
+            ]),
+            created_at: output::tabled::Timestamp(100),
+            ..content()
+        };
+        let filter = |labels: &[&str]| Filter {
+            labels: labels.iter().map(|raw| parse_label(raw).unwrap()).collect(),
+            ..Default::default()
+        };
+
+        assert!(filter(&[]).matches(&content));
+        assert!(filter(&["year:2024", "topic:news"]).matches(&content));
+        assert!(filter(&["page:3"]).matches(&content));
+        assert!(!filter(&["year:2023"]).matches(&content));
+        assert!(!filter(&["topic:news", "lang:en"]).matches(&content));
+
+        assert!(Filter {
+            graph: Some("embed".to_string()),
+            mime_type: Some("application/pdf".to_string()),
+            ..Default::default()
+        }
+        .matches(&content));
+        assert!(!Filter {
+            graph: Some("ocr".to_string()),
+            ..Default::default()
+        }
+        .matches(&content));
+        assert!(!Filter {
+            since: Some(output::tabled::Timestamp(101)),
+            ..Default::default()
+        }
+        .matches(&content));
+        assert!(!Filter {
+            before: Some(output::tabled::Timestamp(100)),
+            ..Default::default()
+        }
+        .matches(&content));
+    }
+
+    #[test]
+    fn extraction_from_tasks() {
+        let tasks = [
+            task("embed", api::TaskOutcome::Success),
+            task("chunk", api::TaskOutcome::Unknown),
+            task("ocr", api::TaskOutcome::Failed),
+        ];
+
+        let extraction = Extraction::of(&content(), &tasks);
+
+        assert_eq!(
+            extraction,
+            Extraction {
+                pending: 1,
+                unscheduled: 0,
+                failed: vec!["ocr on abc".to_string()],
+            }
+        );
+        assert_eq!(extraction.status(), "failed");
+    }
+
+    #[test]
+    fn extraction_waits_for_tasks_to_be_scheduled() {
+        let extraction = Extraction::of(&content(), &[]);
+
+        assert_eq!(extraction.unscheduled, 1);
+        assert!(!extraction.done());
+        assert_eq!(extraction.status(), "no tasks");
+    }
+
+    #[test]
+    fn extraction_statuses() {
+        let running = Extraction::of(&content(), &[task("embed", api::TaskOutcome::Unknown)]);
+        let extracted = Extraction::of(&content(), &[task("embed", api::TaskOutcome::Success)]);
+
+        assert_eq!(running.status(), "timed out");
+        assert_eq!(extracted.status(), "extracted");
+        assert!(extracted.done());
+    }
+
+    #[tokio::test]
+    async fn wait_times_out_with_poll_errors() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let client = client::Client::new(&addr)
+            .unwrap()
+            .with_namespace("default");
+        let results = wait(&client, &["abc".to_string()], Some(Duration::ZERO), false)
+            .await
+            .unwrap();
+
+        assert_eq!(results[0].status, "timed out");
+        assert!(!results[0].error.is_empty());
+    }
+
+    #[test]
+    fn extraction_without_graphs() {
+        let content = api::ContentMetadata {
+            extraction_graph_names: vec![],
+            ..content()
+        };
+
+        assert_eq!(Extraction::of(&content, &[]), Extraction::default());
+        assert_eq!(Extraction::default().status(), "extracted");
+    }
+
+    fn node(id: &str, parent_id: &str) -> api::ContentMetadata {
+        api::ContentMetadata {
+            id: id.to_string(),
+            parent_id: parent_id.to_string(),
+            name: format!("{}.txt", id),
+            ..Default::default()
+        }
+    }
+
+    // root
+    // ├── a
+    // │   └── a1
+    // └── b
+    fn tree(orphans: bool) -> ContentTree {
+        let mut children = HashMap::from([
+            (
+                "root".to_string(),
+                vec![node("b", "root"), node("a", "root")],
+            ),
+            ("a".to_string(), vec![node("a1", "a")]),
+        ]);
+        if orphans {
+            children.insert("gone".to_string(), vec![node("orphan", "gone")]);
+        }
+
+        ContentTree::build(node("root", ""), &mut children)
+    }
+
+    fn rows(tree: &ContentTree) -> Vec<String> {
+        let mut rows = vec![];
+        tree.rows("", true, true, &mut rows);
+
+        rows.into_iter().map(|row| row.id).collect()
+    }
+
+    #[test]
+    fn tree_rows() {
+        assert_eq!(
+            rows(&tree(false)),
+            vec!["root", "├── a", "│   └── a1", "└── b"]
+        );
+    }
+
+    #[test]
+    fn tree_leaves_out_orphans() {
+        assert_eq!(rows(&tree(true)), rows(&tree(false)));
+    }
+
+    #[test]
+    fn tree_with_a_cycle() {
+        let mut children = HashMap::from([
+            ("a".to_string(), vec![node("b", "a")]),
+            ("b".to_string(), vec![node("a", "b")]),
+        ]);
+
+        let tree = ContentTree::build(node("a", "b"), &mut children);
+
+        assert_eq!(rows(&tree), vec!["a", "└── b", "    └── a"]);
+    }
+
+    #[test]
+    fn tree_json() {
+        let value = serde_json::to_value(tree(false)).unwrap();
+
+        assert_eq!(value["id"], "root");
+        assert_eq!(value["name"], "root.txt");
+        assert_eq!(value["children"][0]["id"], "a");
+        assert_eq!(value["children"][0]["parent_id"], "root");
+        assert_eq!(value["children"][0]["children"][0]["id"], "a1");
+        assert_eq!(
+            value["children"][0]["children"][0]["children"],
+            serde_json::json!([])
+        );
+        assert_eq!(value["children"][1]["id"], "b");
+        assert_eq!(value["children"].as_array().unwrap().len(), 2);
+    }
+
+    const BODY: &[u8] = b"hello, world";
+
+    fn sha256(data: &[u8]) -> String {
+        digest::digest(&digest::SHA256, data)
+            .as_ref()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    fn download() -> api::ContentMetadata {
+        api::ContentMetadata {
+            size: Bytes(BODY.len() as u64),
+            hash: sha256(BODY),
+            ..content()
+        }
+    }
+
+    #[test]
+    fn verify_matching() {
+        let content = download();
+
+        assert!(verify(&content, 12, digest::digest(&digest::SHA256, BODY)).is_ok());
+    }
+
+    #[test]
+    fn verify_size_mismatch() {
+        let err = verify(&download(), 5, digest::digest(&digest::SHA256, BODY)).unwrap_err();
+
+        assert!(err.to_string().contains("size mismatch"), "{}", err);
+    }
+
+    #[test]
+    fn verify_hash_mismatch() {
+        let err = verify(
+            &download(),
+            12,
+            digest::digest(&digest::SHA256, b"hello, there"),
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("hash mismatch"), "{}", err);
+    }
+
+    #[test]
+    fn verify_skips_what_the_server_left_out() {
+        let content = api::ContentMetadata {
+            size: Bytes(0),
+            hash: String::new(),
+            ..download()
+        };
+
+        assert!(verify(&content, 5, digest::digest(&digest::SHA256, b"other")).is_ok());
+    }
+
+    // Serves BODY for every request, honouring `Range` only when `ranges` is
+    // set. Returns the address and the ranges that were asked for.
+    async fn serve(ranges: bool) -> (String, std::sync::Arc<std::sync::Mutex<Vec<u64>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+        let requested = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let seen = requested.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = vec![];
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+
+                let offset = String::from_utf8_lossy(&request)
+                    .lines()
+                    .find_map(|line| {
+                        line.to_lowercase()
+                            .strip_prefix("range: bytes=")
+                            .map(String::from)
+                    })
+                    .and_then(|range| range.trim_end_matches('-').parse::<u64>().ok());
+                if let Some(offset) = offset {
+                    seen.lock().unwrap().push(offset);
+                }
+
+                let (status, offset) = match offset.filter(|_| ranges) {
+                    Some(offset) => ("206 Partial Content", offset as usize),
+                    None => ("200 OK", 0),
+                };
+                let head = format!(
+                    "HTTP/1.1 {}\r\ncontent-length: {}\r\ncontent-range: bytes {}-{}/{}\r\nconnection: close\r\n\r\n",
+                    status,
+                    BODY.len() - offset,
+                    offset,
+                    BODY.len() - 1,
+                    BODY.len()
+                );
+                socket.write_all(head.as_bytes()).await.unwrap();
+                socket.write_all(&BODY[offset..]).await.unwrap();
+            }
+        });
+
+        (addr, requested)
+    }
+
+    async fn save_with(
+        part: Option<&[u8]>,
+        ranges: bool,
+        content: api::ContentMetadata,
+    ) -> (Result<()>, PathBuf, Vec<u64>) {
+        static COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+        let dir = std::env::temp_dir().join(format!(
+            "indexify-save-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+        ));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let path = dir.join("abc");
+        if let Some(part) = part {
+            tokio::fs::write(part_path(&path), part).await.unwrap();
+        }
+
+        let (addr, requested) = serve(ranges).await;
+        let client = client::Client::new(&addr)
+            .unwrap()
+            .with_namespace("default");
+
+        let result = save(&client, &content, &path, true, &ProgressBar::hidden()).await;
+        let requested = requested.lock().unwrap().clone();
+
+        (result, path, requested)
+    }
+
+    async fn saved(path: &Path) -> Vec<u8> {
+        let data = tokio::fs::read(path).await.unwrap();
+        tokio::fs::remove_dir_all(path.parent().unwrap())
+            .await
+            .unwrap();
+
+        data
+    }
+
+    #[tokio::test]
+    async fn save_resumes_from_the_part() {
+        let (result, path, requested) = save_with(Some(&BODY[..5]), true, download()).await;
+
+        result.unwrap();
+        assert_eq!(requested, vec![5]);
+        assert_eq!(saved(&path).await, BODY);
+    }
+
+    #[tokio::test]
+    async fn save_starts_over_when_range_is_ignored() {
+        let (result, path, requested) = save_with(Some(&BODY[..5]), false, download()).await;
+
+        result.unwrap();
+        assert_eq!(requested, vec![5]);
+        assert_eq!(saved(&path).await, BODY);
+    }
+
+    #[tokio::test]
+    async fn save_starts_over_when_the_part_is_too_large() {
+        let (result, path, requested) = save_with(Some(&[b'x'; 64]), true, download()).await;
+
+        result.unwrap();
+        assert!(requested.is_empty());
+        assert_eq!(saved(&path).await, BODY);
+    }
+
+    #[tokio::test]
+    async fn save_removes_corrupt_parts() {
+        let content = api::ContentMetadata {
+            hash: sha256(b"something else"),
+            ..download()
+        };
+        let (result, path, _) = save_with(Some(&BODY[..5]), true, content).await;
+
+        assert!(result.unwrap_err().to_string().contains("hash mismatch"));
+        assert!(!part_path(&path).exists());
+        assert!(!path.exists());
+        tokio::fs::remove_dir_all(path.parent().unwrap())
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn export_path_default_template() {
+        assert_eq!(
+            export_path("{graph}/{id}-{name}", None, &content()),
+            PathBuf::from("summarize/abc-report.pdf")
+        );
+    }
+
+    #[test]
+    fn export_path_graph_filter() {
+        assert_eq!(
+            export_path("{graph}/{id}", Some("embed"), &content()),
+            PathBuf::from("embed/abc")
+        );
+    }
+
+    #[test]
+    fn export_path_without_graph() {
+        let content = api::ContentMetadata {
+            extraction_graph_names: vec![],
+            ..content()
+        };
+
+        assert_eq!(
+            export_path("{graph}/{id}", None, &content),
+            PathBuf::from("_/abc")
+        );
+    }
+
+    #[test]
+    fn export_path_escapes_separators() {
+        let content = api::ContentMetadata {
+            name: "../../etc/passwd".to_string(),
+            ..content()
+        };
+
+        assert_eq!(
+            export_path("{name}", None, &content),
+            PathBuf::from(".._.._etc_passwd")
+        );
+    }
+
+    #[test]
+    fn export_path_empty_and_dot_values() {
+        let content = api::ContentMetadata {
+            name: "..".to_string(),
+            ..content()
+        };
+
+        assert_eq!(
+            export_path("{source}/{name}", None, &content),
+            PathBuf::from("_/_")
+        );
+    }
+
+    #[test]
+    fn export_path_stays_relative() {
+        assert_eq!(
+            export_path("/../{namespace}/./{id}", None, &content()),
+            PathBuf::from("default/abc")
+        );
+    }
 }
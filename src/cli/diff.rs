@@ -0,0 +1,222 @@
+use clap::Parser;
+use console::Style;
+use eyre::{eyre, Result};
+use serde_json::Value;
+use similar::{ChangeTag, TextDiff};
+use tracing::instrument;
+
+use crate::{
+    api::{DataNamespace, ExtractionGraph},
+    cli::{apply::Resource, graph, namespace},
+    client,
+    command::{self, Command},
+    derive::Command,
+    file::File,
+};
+
+/// Show how a namespace or graph definition differs from the server, exits
+/// with 1 when there are differences and 2 on errors
+#[derive(Debug, Parser, Command)]
+pub struct Diff {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(from_global)]
+    pub namespace: String,
+
+    /// Path to the namespace or graph file
+    #[clap(short, long)]
+    pub file: File<Resource>,
+}
+
+// Order isn't significant to the server.
+fn sort(graphs: &mut [ExtractionGraph]) {
+    graphs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    for graph in graphs {
+        graph
+            .extraction_policies
+            .sort_by(|a, b| a.name.cmp(&b.name));
+    }
+}
+
+fn render(value: &Value) -> Result<String> {
+    match value {
+        Value::Null => Ok(String::new()),
+        value => Ok(serde_yaml::to_string(value)?),
+    }
+}
+
+// Prints a unified diff and returns whether there were any changes.
+fn print(path: &str, live: &str, local: &str) -> bool {
+    if live == local {
+        return false;
+    }
+
+    let diff = TextDiff::from_lines(live, local);
+
+    let bold = Style::new().bold();
+    println!("{}", bold.apply_to(format!("--- live/{}", path)));
+    println!("{}", bold.apply_to(format!("+++ local/{}", path)));
+
+    for hunk in diff.unified_diff().iter_hunks() {
+        println!("{}", Style::new().cyan().apply_to(hunk.header()));
+
+        for change in hunk.iter_changes() {
+            let (sign, style) = match change.tag() {
+                ChangeTag::Delete => ("-", Style::new().red()),
+                ChangeTag::Insert => ("+", Style::new().green()),
+                ChangeTag::Equal => (" ", Style::new()),
+            };
+
+            println!(
+                "{}",
+                style.apply_to(format!("{}{}", sign, change.value().trim_end_matches('\n')))
+            );
+        }
+    }
+
+    true
+}
+
+impl Diff {
+    async fn live(&self, namespace: &str) -> Result<Option<DataNamespace>> {
+        match self.api_server.get::<DataNamespace>(namespace).await {
+            Ok(ns) => Ok(Some(ns)),
+            Err(err) if client::is_not_found(&err) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Command for Diff {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let resource = match self.file.clone() {
+            File::None => return Err(eyre!("No input file provided")),
+            File::Some(resource) => resource,
+        };
+
+        let (path, live, local) = match resource {
+            Resource::Namespace(mut ns) => {
+                // A namespace that doesn't exist yet shows up as entirely added.
+                let live = match self.live(&ns.name).await? {
+                    Some(mut live) => {
+                        sort(&mut live.extraction_graphs);
+                        namespace::export(&live.name, &live.extraction_graphs)?
+                    }
+                    None => Value::Null,
+                };
+
+                sort(&mut ns.extraction_graphs);
+
+                (
+                    format!("namespace/{}", ns.name),
+                    live,
+                    namespace::export(&ns.name, &ns.extraction_graphs)?,
+                )
+            }
+            Resource::Graph(mut graph) => {
+                let namespace = match graph.namespace.as_str() {
+                    "" => self.namespace.clone(),
+                    namespace => namespace.to_string(),
+                };
+
+                let graphs = match self.live(&namespace).await? {
+                    Some(ns) => ns.extraction_graphs,
+                    None => vec![],
+                };
+
+                // A graph that doesn't exist yet shows up as entirely added.
+                let mut live: Vec<_> = graphs
+                    .into_iter()
+                    .filter(|g| g.name == graph.name)
+                    .collect();
+
+                sort(&mut live);
+                sort(std::slice::from_mut(&mut graph));
+
+                (
+                    format!("graph/{}/{}", namespace, graph.name),
+                    match live.first() {
+                        Some(live) => graph::export(live)?,
+                        None => Value::Null,
+                    },
+                    graph::export(&graph)?,
+                )
+            }
+        };
+
+        if print(&path, &render(&live)?, &render(&local)?) {
+            return Err(command::Exit(1).into());
+        }
+
+        Ok(())
+    }
+
+    fn failure_code(&self) -> i32 {
+        2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ExtractionPolicy;
+
+    fn graph(namespace: &str, id: &str) -> ExtractionGraph {
+        ExtractionGraph {
+            id: id.to_string(),
+            name: "summarize".to_string(),
+            namespace: namespace.to_string(),
+            description: None.into(),
+            extraction_policies: vec![ExtractionPolicy {
+                id: id.to_string(),
+                extractor: "tensorlake/minilm-l6".to_string(),
+                name: "embed".to_string(),
+                filters_eq: None,
+                input_params: None.into(),
+                content_source: None.into(),
+                graph_name: namespace.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn server_fields_are_left_out() {
+        let live = graph::export(&graph("default", "abc")).unwrap();
+        let local = graph::export(&graph("", "")).unwrap();
+
+        assert_eq!(render(&live).unwrap(), render(&local).unwrap());
+        assert!(live.get("id").is_none());
+        assert!(live.get("namespace").is_none());
+        assert!(live["extraction_policies"][0].get("graph_name").is_none());
+        assert!(live["extraction_policies"][0].get("id").is_none());
+    }
+
+    #[test]
+    fn namespace_graphs_by_name() {
+        let mut graphs = vec![
+            ExtractionGraph {
+                name: "b".to_string(),
+                ..graph("default", "1")
+            },
+            ExtractionGraph {
+                name: "a".to_string(),
+                ..graph("default", "2")
+            },
+        ];
+        sort(&mut graphs);
+
+        let live = namespace::export("default", &graphs).unwrap();
+
+        assert_eq!(live["extraction_graphs"][0]["name"], "a");
+        assert!(live["extraction_graphs"][0].get("id").is_none());
+    }
+
+    #[test]
+    fn missing_renders_as_empty() {
+        assert_eq!(render(&Value::Null).unwrap(), "");
+    }
+}
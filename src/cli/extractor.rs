@@ -1,8 +1,16 @@
 use clap::{Parser, Subcommand};
-use eyre::Result;
+use eyre::{eyre, Result};
+use serde::Serialize;
+use serde_json::Value;
+use tabled::Tabled;
 use tracing::instrument;
 
-use crate::{api, client, command::Command, derive::Command, output};
+use crate::{
+    api, client,
+    command::Command,
+    derive::{Columns, Command},
+    output,
+};
 
 /// Examine and manipulate extractors
 #[derive(Debug, Parser, Command)]
@@ -13,18 +21,204 @@ pub struct Extractor {
 
 #[derive(Debug, Subcommand, Command)]
 pub enum ExtractorCmd {
+    Describe(Describe),
     List(List),
 }
 
 impl Command for Extractor {}
 
+/// Show every field of an extractor, including its parameters and outputs
+#[derive(Debug, Parser, Command)]
+pub struct Describe {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
+    /// Name of the extractor
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Tabled, Columns)]
+pub struct Summary {
+    pub name: String,
+    pub description: String,
+    pub input_mime_types: String,
+}
+
+/// A property of a JSON schema, such as one of the input parameters.
+#[derive(Debug, Serialize, Tabled, Columns)]
+pub struct Property {
+    pub name: String,
+    #[tabled(rename = "type")]
+    pub kind: String,
+    #[tabled(display_with = "required")]
+    pub required: bool,
+    pub default: String,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize, Tabled, Columns)]
+pub struct Output {
+    pub name: String,
+    #[tabled(rename = "type")]
+    pub kind: &'static str,
+    pub schema: String,
+}
+
+fn required(required: &bool) -> String {
+    match required {
+        true => "yes".to_string(),
+        false => String::new(),
+    }
+}
+
+// Optional fields are usually a union with null, such as `integer | null`.
+fn schema_type(schema: &Value) -> String {
+    if let Some(kinds) = schema.get("anyOf").or(schema.get("oneOf")) {
+        return kinds
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(schema_type)
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return values
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+
+    match schema.get("type") {
+        Some(Value::String(kind)) if kind == "array" => match schema.get("items") {
+            Some(items) => format!("array of {}", schema_type(items)),
+            None => kind.clone(),
+        },
+        Some(Value::String(kind)) => kind.clone(),
+        Some(Value::Array(kinds)) => kinds
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" | "),
+        _ => match schema.get("$ref").and_then(Value::as_str) {
+            Some(reference) => reference.rsplit('/').next().unwrap_or_default().to_string(),
+            None => "any".to_string(),
+        },
+    }
+}
+
+fn properties(schema: &Value) -> Vec<Property> {
+    let required: Vec<_> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+
+    schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .map(|(name, property)| Property {
+            name: name.clone(),
+            kind: schema_type(property),
+            required: required.contains(&name.as_str()),
+            default: property
+                .get("default")
+                .map(Value::to_string)
+                .unwrap_or_default(),
+            description: property
+                .get("description")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+        })
+        .collect()
+}
+
+fn outputs(extractor: &api::ExtractorDescription) -> Result<Vec<Output>> {
+    let mut outputs = extractor
+        .outputs
+        .iter()
+        .map(|(name, schema)| {
+            let (kind, schema) = match schema {
+                api::ExtractorOutputSchema::Embedding(embedding) => {
+                    ("embedding", embedding.to_string())
+                }
+                api::ExtractorOutputSchema::Metadata(schema) => {
+                    let fields = properties(schema);
+
+                    let schema = match (fields.is_empty(), schema) {
+                        (true, Value::Null) => String::new(),
+                        (true, schema) => serde_yaml::to_string(schema)?.trim_end().to_string(),
+                        (false, _) => fields
+                            .iter()
+                            .map(|field| format!("{}: {}", field.name, field.kind))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    };
+
+                    ("metadata", schema)
+                }
+            };
+
+            Ok(Output {
+                name: name.clone(),
+                kind,
+                schema,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    outputs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(outputs)
+}
+
+#[async_trait::async_trait]
+impl Command for Describe {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let extractors: Vec<api::ExtractorDescription> = self.api_server.list().await?;
+
+        let Some(extractor) = extractors.iter().find(|e| e.name == self.name) else {
+            return Err(eyre!("Extractor not found: {}", self.name));
+        };
+
+        // Everything else gets the description as the server sent it, the
+        // schemas are only rendered for people.
+        if !self.output.pretty() {
+            return self.output.describe(extractor);
+        }
+
+        self.output.describe(&Summary {
+            name: extractor.name.clone(),
+            description: extractor.description.clone(),
+            input_mime_types: output::tabled::display(&extractor.input_mime_types),
+        })?;
+
+        self.output
+            .describe_section("Input parameters", &properties(&extractor.input_params))?;
+
+        self.output
+            .describe_section("Outputs", &outputs(extractor)?)
+    }
+}
+
 /// List all the registered extractors
 #[derive(Debug, Parser, Command)]
 pub struct List {
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub api_server: client::Client,
 
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub output: output::Format,
 
     #[clap(from_global)]
@@ -39,4 +233,125 @@ impl Command for List {
 
         self.output.list(&extractors)
     }
+
+    fn watchable(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn schema() -> Value {
+        json!({
+            "title": "Params",
+            "type": "object",
+            "required": ["model"],
+            "properties": {
+                "model": {
+                    "type": "string",
+                    "description": "Model to run"
+                },
+                "chunk_size": {
+                    "type": "integer",
+                    "default": 512
+                },
+                "overlap": {
+                    "anyOf": [{"type": "integer"}, {"type": "null"}],
+                    "default": null
+                },
+                "mode": {"enum": ["fast", "accurate"]},
+                "pages": {"type": "array", "items": {"type": "integer"}},
+                "tags": {"type": ["string", "null"]},
+                "language": {"$ref": "#/definitions/Language"},
+                "options": {}
+            },
+            "definitions": {
+                "Language": {"enum": ["en", "fr"]}
+            }
+        })
+    }
+
+    fn property<'a>(properties: &'a [Property], name: &str) -> &'a Property {
+        properties.iter().find(|p| p.name == name).unwrap()
+    }
+
+    #[test]
+    fn schema_types() {
+        let schema = schema();
+        let kind = |name: &str| schema_type(&schema["properties"][name]);
+
+        assert_eq!(kind("model"), "string");
+        assert_eq!(kind("overlap"), "integer | null");
+        assert_eq!(kind("mode"), r#""fast" | "accurate""#);
+        assert_eq!(kind("pages"), "array of integer");
+        assert_eq!(kind("tags"), "string | null");
+        assert_eq!(kind("language"), "Language");
+        assert_eq!(kind("options"), "any");
+        assert_eq!(schema_type(&json!({"type": "array"})), "array");
+    }
+
+    #[test]
+    fn properties_of_schema() {
+        let properties = properties(&schema());
+
+        assert_eq!(properties.len(), 8);
+
+        let model = property(&properties, "model");
+        assert!(model.required);
+        assert_eq!(model.default, "");
+        assert_eq!(model.description, "Model to run");
+
+        let chunk_size = property(&properties, "chunk_size");
+        assert!(!chunk_size.required);
+        assert_eq!(chunk_size.default, "512");
+
+        assert_eq!(property(&properties, "overlap").default, "null");
+    }
+
+    #[test]
+    fn properties_without_any() {
+        assert!(properties(&json!({"type": "object"})).is_empty());
+        assert!(properties(&json!("string")).is_empty());
+        assert!(properties(&Value::Null).is_empty());
+    }
+
+    #[test]
+    fn outputs_by_name() {
+        let extractor: api::ExtractorDescription = serde_json::from_value(json!({
+            "name": "tensorlake/minilm-l6",
+            "input_mime_types": ["text/plain"],
+            "description": "Embeddings",
+            "input_params": schema(),
+            "outputs": {
+                "embedding": {"embedding": {"dim": 384, "distance": "dot"}},
+                "entities": {"metadata": {
+                    "type": "object",
+                    "properties": {"name": {"type": "string"}, "score": {"type": "number"}}
+                }},
+                "raw": {"metadata": {"type": "string"}},
+                "unknown": {"metadata": null}
+            }
+        }))
+        .unwrap();
+
+        let outputs = outputs(&extractor).unwrap();
+        let summary: Vec<_> = outputs
+            .iter()
+            .map(|o| (o.name.as_str(), o.kind, o.schema.as_str()))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("embedding", "embedding", "384-dot"),
+                ("entities", "metadata", "name: string\nscore: number"),
+                ("raw", "metadata", "type: string"),
+                ("unknown", "metadata", ""),
+            ]
+        );
+    }
 }
@@ -1,14 +1,17 @@
 use clap::{Parser, Subcommand};
 use eyre::{eyre, Result};
-use tracing::instrument;
+use serde::Serialize;
+use serde_json::Value;
+use tabled::Tabled;
+use tracing::{info, instrument};
 
 use crate::{
-    api::{DataNamespace, ExtractionGraph},
+    api::{self, DataNamespace, ExtractionGraph},
     client,
     command::Command,
-    derive::Command,
+    derive::{Columns, Command},
     file::File,
-    output,
+    output, prompt,
 };
 
 /// Interact with extraction graphs
@@ -21,6 +24,9 @@ pub struct Graph {
 #[derive(Debug, Subcommand, Command)]
 pub enum GraphCmd {
     Create(Create),
+    Delete(Delete),
+    Describe(Describe),
+    Export(Export),
     Get(Get),
     List(List),
 }
@@ -30,10 +36,10 @@ impl Command for Graph {}
 /// Create a new graph
 #[derive(Debug, Parser, Command)]
 pub struct Create {
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub api_server: client::Client,
 
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub output: output::Format,
 
     #[clap(from_global)]
@@ -67,13 +73,233 @@ impl Command for Create {
     }
 }
 
-/// Get a graph by name
+/// Delete a graph, along with its extraction policies, indexes and content
 #[derive(Debug, Parser, Command)]
-pub struct Get {
+pub struct Delete {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
     #[clap(from_global)]
+    pub namespace: String,
+
+    /// Name of the graph
+    pub name: String,
+
+    /// Don't ask for confirmation
+    #[clap(short, long)]
+    pub yes: bool,
+
+    /// Only show what would be deleted
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+/// Something that is deleted along with a graph or namespace.
+#[derive(Debug, Serialize, Tabled, Columns)]
+pub struct Affected {
+    pub kind: &'static str,
+    pub name: String,
+}
+
+/// The policies and indexes that go away with a graph. Indexes are named after
+/// the graph and policy that populate them.
+pub fn affected(graph: &ExtractionGraph, indexes: &[api::Index]) -> Vec<Affected> {
+    let mut affected: Vec<Affected> = graph
+        .extraction_policies
+        .iter()
+        .map(|policy| Affected {
+            kind: "extraction policy",
+            name: policy.name.clone(),
+        })
+        .collect();
+
+    let prefix = format!("{}.", graph.name);
+    affected.extend(
+        indexes
+            .iter()
+            .filter(|index| index.name.starts_with(&prefix))
+            .map(|index| Affected {
+                kind: "index",
+                name: index.name.clone(),
+            }),
+    );
+
+    affected
+}
+
+/// Graphs are read through the namespace they belong to.
+pub async fn find(
+    api_server: &client::Client,
+    namespace: &str,
+    name: &str,
+) -> Result<ExtractionGraph> {
+    let namespace = match api_server.get::<DataNamespace>(namespace).await {
+        Ok(ns) => ns,
+        Err(err) if client::is_not_found(&err) => {
+            return Err(eyre!("Namespace not found: {}", namespace))
+        }
+        Err(err) => return Err(err),
+    };
+
+    namespace
+        .extraction_graphs
+        .into_iter()
+        .find(|g| g.name == name)
+        .ok_or_else(|| eyre!("Graph not found: {}", name))
+}
+
+pub async fn content_count(client: &client::Client, graph: Option<&str>) -> Result<Affected> {
+    let page = client
+        .list_page::<Vec<api::ContentMetadata>>(&api::ListContent {
+            graph: graph.map(String::from),
+            limit: Some(1),
+            return_total: true,
+            ..Default::default()
+        })
+        .await?;
+
+    Ok(Affected {
+        kind: "content",
+        name: format!("{} items", page.total.unwrap_or(page.items.len() as u64)),
+    })
+}
+
+#[async_trait::async_trait]
+impl Command for Delete {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let client = self.api_server.clone().with_namespace(&self.namespace);
+
+        let graph = find(&client, &self.namespace, &self.name).await?;
+
+        let indexes: Vec<api::Index> = client.list().await?;
+        let mut affected = affected(&graph, &indexes);
+        affected.push(content_count(&client, Some(&graph.name)).await?);
+
+        self.output.list(&affected)?;
+
+        if self.dry_run {
+            return Ok(());
+        }
+
+        prompt::confirm(
+            &format!(
+                "Delete graph {} in namespace {} and everything listed above?",
+                self.name, self.namespace
+            ),
+            self.yes,
+        )?;
+
+        client.delete_by_id::<ExtractionGraph>(&self.name).await?;
+
+        info!(graph = self.name, "deleted");
+
+        if self.output.pretty() {
+            println!("Deleted graph {} and everything listed above", self.name);
+        }
+
+        Ok(())
+    }
+}
+
+/// Show every field of a graph and its extraction policies
+#[derive(Debug, Parser, Command)]
+pub struct Describe {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
+    #[clap(from_global)]
+    pub namespace: String,
+
+    /// Name of the graph
+    pub name: String,
+}
+
+#[async_trait::async_trait]
+impl Command for Describe {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let graph = find(&self.api_server, &self.namespace, &self.name).await?;
+
+        self.output.describe(&graph)
+    }
+}
+
+/// Print a graph in the format `graph create` accepts, without the fields the
+/// server fills in
+#[derive(Debug, Parser, Command)]
+pub struct Export {
+    #[clap(flatten)]
     pub api_server: client::Client,
 
+    #[clap(flatten)]
+    pub output: output::Format,
+
     #[clap(from_global)]
+    pub namespace: String,
+
+    /// Name of the graph
+    pub name: String,
+}
+
+// Unset and empty are the same to the server.
+fn strip(value: &mut Value, fields: &[&str]) {
+    if let Some(map) = value.as_object_mut() {
+        map.retain(|key, value| {
+            !fields.contains(&key.as_str())
+                && !value.is_null()
+                && !value.as_object().is_some_and(|m| m.is_empty())
+        });
+    }
+}
+
+/// A graph without its ids and the names of its parents, which the server
+/// fills in. Filters are written in the `key:value` form that graph files use.
+pub fn export(graph: &ExtractionGraph) -> Result<Value> {
+    let mut value = serde_json::to_value(graph)?;
+    strip(&mut value, &["id", "namespace"]);
+
+    let Some(policies) = value["extraction_policies"].as_array_mut() else {
+        return Ok(value);
+    };
+
+    for (policy, value) in graph.extraction_policies.iter().zip(policies) {
+        if policy.filters_eq.as_ref().is_some_and(|f| !f.is_empty()) {
+            value["filters_eq"] = api::utils::serialize_labels_eq_filter(
+                &policy.filters_eq,
+                serde_json::value::Serializer,
+            )?;
+        }
+
+        strip(value, &["id", "graph_name"]);
+    }
+
+    Ok(value)
+}
+
+#[async_trait::async_trait]
+impl Command for Export {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let graph = find(&self.api_server, &self.namespace, &self.name).await?;
+
+        self.output.document(&export(&graph)?)
+    }
+}
+
+/// Get a graph by name
+#[derive(Debug, Parser, Command)]
+pub struct Get {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
     pub output: output::Format,
 
     #[clap(from_global)]
@@ -87,27 +313,23 @@ pub struct Get {
 impl Command for Get {
     #[instrument]
     async fn run(&self) -> Result<()> {
-        let namespaces: Vec<DataNamespace> = self.api_server.list().await?;
+        let graph = find(&self.api_server, &self.namespace, &self.name).await?;
 
-        let ns = match namespaces.iter().find(|ns| ns.name == self.namespace) {
-            Some(ns) => &ns.extraction_graphs,
-            None => return Err(eyre::eyre!("Namespace not found: {}", self.namespace)),
-        };
+        self.output.item(&graph)
+    }
 
-        match ns.iter().find(|g| g.name == self.name) {
-            Some(g) => self.output.item(g),
-            None => Err(eyre::eyre!("Graph not found: {}", self.name)),
-        }
+    fn watchable(&self) -> bool {
+        true
     }
 }
 
 /// List all the graphs in a namespace
 #[derive(Debug, Parser, Command)]
 pub struct List {
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub api_server: client::Client,
 
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub output: output::Format,
 
     #[clap(from_global)]
@@ -125,4 +347,88 @@ impl Command for List {
             None => Err(eyre::eyre!("Namespace not found: {}", self.namespace)),
         }
     }
+
+    fn watchable(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::builder::TypedValueParser;
+
+    use super::*;
+    use crate::api::ExtractionPolicy;
+
+    fn graph() -> ExtractionGraph {
+        ExtractionGraph {
+            id: "abc".to_string(),
+            name: "summarize".to_string(),
+            namespace: "default".to_string(),
+            description: Some("Summaries".to_string()).into(),
+            extraction_policies: vec![ExtractionPolicy {
+                id: "def".to_string(),
+                extractor: "tensorlake/minilm-l6".to_string(),
+                name: "embed".to_string(),
+                filters_eq: Some(
+                    [
+                        ("year".to_string(), serde_json::json!(2024)),
+                        ("topic".to_string(), serde_json::json!("news")),
+                    ]
+                    .into(),
+                ),
+                input_params: Some(serde_json::json!({ "chunk_size": 512 })).into(),
+                content_source: Some("ingestion".to_string()).into(),
+                graph_name: "summarize".to_string(),
+            }],
+        }
+    }
+
+    fn parse(raw: &str) -> ExtractionGraph {
+        let dir = std::env::temp_dir().join(format!("indexify-graph-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("graph.yaml");
+        std::fs::write(&path, raw).unwrap();
+
+        let parsed = File::<ExtractionGraph>::default().parse_ref(
+            &clap::Command::new("test"),
+            None,
+            path.as_os_str(),
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        match parsed.unwrap() {
+            File::Some(graph) => graph,
+            File::None => panic!("no graph parsed"),
+        }
+    }
+
+    #[test]
+    fn export_leaves_out_server_fields() {
+        let value = export(&graph()).unwrap();
+
+        assert!(value.get("id").is_none());
+        assert!(value.get("namespace").is_none());
+
+        let policy = &value["extraction_policies"][0];
+        assert!(policy.get("id").is_none());
+        assert!(policy.get("graph_name").is_none());
+        assert_eq!(policy["filters_eq"], "topic:news,year:2024");
+    }
+
+    #[test]
+    fn export_parses_as_a_graph_file() {
+        let raw = serde_yaml::to_string(&export(&graph()).unwrap()).unwrap();
+        let parsed = parse(&raw);
+
+        assert_eq!(parsed.name, "summarize");
+        assert!(parsed.id.is_empty());
+        assert!(parsed.namespace.is_empty());
+
+        let policy = &parsed.extraction_policies[0];
+        assert_eq!(policy.filters_eq, graph().extraction_policies[0].filters_eq);
+        assert!(policy.graph_name.is_empty());
+
+        assert_eq!(export(&parsed).unwrap(), export(&graph()).unwrap());
+    }
 }
@@ -1,5 +1,5 @@
 use clap::{Parser, Subcommand};
-use eyre::Result;
+use eyre::{eyre, Result};
 use tracing::instrument;
 
 use crate::{api, client, command::Command, derive::Command, output};
@@ -13,18 +13,54 @@ pub struct Index {
 
 #[derive(Debug, Subcommand, Command)]
 pub enum IndexCmd {
+    Describe(Describe),
     List(List),
+    Search(Search),
 }
 
 impl Command for Index {}
 
+/// Show every field of an index, including its embedding schema
+#[derive(Debug, Parser, Command)]
+pub struct Describe {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
+    #[clap(from_global)]
+    pub namespace: String,
+
+    /// Name of the index
+    pub name: String,
+}
+
+#[async_trait::async_trait]
+impl Command for Describe {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let indexes: Vec<api::Index> = self
+            .api_server
+            .clone()
+            .with_namespace(&self.namespace)
+            .list()
+            .await?;
+
+        match indexes.iter().find(|i| i.name == self.name) {
+            Some(index) => self.output.describe(index),
+            None => Err(eyre!("Index not found: {}", self.name)),
+        }
+    }
+}
+
 /// List all the indexes in a namespace
 #[derive(Debug, Parser, Command)]
 pub struct List {
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub api_server: client::Client,
 
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub output: output::Format,
 
     #[clap(from_global)]
@@ -44,4 +80,64 @@ impl Command for List {
 
         self.output.list(&indexes)
     }
+
+    fn watchable(&self) -> bool {
+        true
+    }
+}
+
+/// Search an index for the content closest to a query
+#[derive(Debug, Parser, Command)]
+pub struct Search {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
+    #[clap(from_global)]
+    pub namespace: String,
+
+    /// Name of the index
+    pub index: String,
+
+    /// Text to search for
+    pub query: String,
+
+    /// Number of results to return
+    #[clap(short = 'k', long, default_value_t = 10)]
+    pub top_k: u64,
+
+    /// Only return content with this label, as key:value, can be repeated
+    #[clap(long, value_name = "KEY:VALUE", value_parser = parse_filter)]
+    pub filter: Vec<String>,
+}
+
+fn parse_filter(raw: &str) -> Result<String> {
+    let (key, value) = api::utils::parse_validate_label_raw(raw)?;
+    api::utils::validate_label_key(&key)?;
+    api::utils::validate_label_value(&value)?;
+
+    Ok(raw.to_string())
+}
+
+#[async_trait::async_trait]
+impl Command for Search {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let resp = self
+            .api_server
+            .clone()
+            .with_namespace(&self.namespace)
+            .query(&api::SearchRequest {
+                index: self.index.clone(),
+                query: self.query.clone(),
+                k: Some(self.top_k),
+                filters: self.filter.clone(),
+                include_content: true,
+            })
+            .await?;
+
+        self.output.list(&resp.results)
+    }
 }
@@ -1,16 +1,18 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, fs, path::PathBuf};
 
 use clap::{Parser, Subcommand};
-use eyre::{eyre, Result};
+use eyre::{eyre, Result, WrapErr};
+use serde_json::Value;
 use tracing::{info, instrument};
 
 use crate::{
-    api::{CreateNamespace, DataNamespace},
+    api::{self, CreateNamespace, DataNamespace, ExtractionGraph},
+    cli::graph,
     client,
     command::Command,
     derive::Command,
     file::File,
-    output,
+    output, prompt,
 };
 
 /// Interact with namespaces
@@ -25,6 +27,9 @@ impl Command for Namespace {}
 #[derive(Debug, Subcommand, Command)]
 pub enum NamespaceCmd {
     Create(Create),
+    Delete(Delete),
+    Describe(Describe),
+    Export(Export),
     Get(Get),
     List(List),
 }
@@ -32,10 +37,10 @@ pub enum NamespaceCmd {
 /// Create a new namespace
 #[derive(Debug, Parser, Command)]
 pub struct Create {
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub api_server: client::Client,
 
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub output: output::Format,
 
     /// Name of the namespace
@@ -57,11 +62,7 @@ impl Command for Create {
                 ..Default::default()
             },
             None => match self.file.clone() {
-                Some(File::Some(mut content)) => {
-                    content.name = self.name.clone().unwrap();
-
-                    content
-                }
+                Some(File::Some(content)) => content,
                 _ => return Err(eyre!("No namespace provided")),
             },
         };
@@ -74,13 +75,177 @@ impl Command for Create {
     }
 }
 
+/// Delete a namespace, along with all of its graphs, indexes and content
+#[derive(Debug, Parser, Command)]
+pub struct Delete {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
+    /// Name of the namespace
+    pub name: String,
+
+    /// Don't ask for confirmation
+    #[clap(short, long)]
+    pub yes: bool,
+
+    /// Only show what would be deleted
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+#[async_trait::async_trait]
+impl Command for Delete {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let client = self.api_server.clone().with_namespace(&self.name);
+
+        let namespace = client.get::<DataNamespace>(&self.name).await?;
+        let indexes: Vec<api::Index> = client.list().await?;
+
+        let mut affected = vec![];
+        for g in &namespace.extraction_graphs {
+            affected.push(graph::Affected {
+                kind: "graph",
+                name: g.name.clone(),
+            });
+            affected.extend(graph::affected(g, &indexes));
+        }
+        affected.push(graph::content_count(&client, None).await?);
+
+        self.output.list(&affected)?;
+
+        if self.dry_run {
+            return Ok(());
+        }
+
+        prompt::confirm(
+            &format!(
+                "Delete namespace {} and everything listed above?",
+                self.name
+            ),
+            self.yes,
+        )?;
+
+        client.delete_by_id::<DataNamespace>(&self.name).await?;
+
+        info!(namespace = self.name, "deleted");
+
+        if self.output.pretty() {
+            println!(
+                "Deleted namespace {} and everything listed above",
+                self.name
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Show every field of a namespace and its graphs
+#[derive(Debug, Parser, Command)]
+pub struct Describe {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
+    /// Name of the namespace
+    pub name: String,
+}
+
+#[async_trait::async_trait]
+impl Command for Describe {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let namespace = self.api_server.get::<DataNamespace>(&self.name).await?;
+
+        self.output.describe(&namespace)
+    }
+}
+
+/// Print a namespace and its graphs in the format `apply` accepts, without the
+/// fields the server fills in
+#[derive(Debug, Parser, Command)]
+pub struct Export {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
+    /// Name of the namespace
+    pub name: String,
+
+    /// Write the namespace and each graph to separate files in this directory
+    /// instead. Graph files leave out the namespace, pass it to `apply` with
+    /// `--namespace`
+    #[clap(short = 'd', long)]
+    pub output_dir: Option<PathBuf>,
+}
+
+/// A namespace as `namespace create --file` and `apply` accept it. Labels
+/// aren't returned by the server, so they can't be included.
+pub fn export(name: &str, graphs: &[ExtractionGraph]) -> Result<Value> {
+    Ok(serde_json::json!({
+        "name": name,
+        "extraction_graphs": graphs
+            .iter()
+            .map(graph::export)
+            .collect::<Result<Vec<_>>>()?,
+    }))
+}
+
+impl Export {
+    fn write(&self, dir: &std::path::Path, name: &str, doc: &Value) -> Result<()> {
+        let (ext, raw) = match self.output.kind {
+            output::Kind::Json => ("json", serde_json::to_string_pretty(doc)? + "\n"),
+            _ => ("yaml", serde_yaml::to_string(doc)?),
+        };
+
+        let path = dir.join(format!("{}.{}", name.replace(['/', '\\'], "_"), ext));
+        fs::write(&path, raw).wrap_err_with(|| format!("unable to write {}", path.display()))?;
+
+        println!("{}", path.display());
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Command for Export {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let namespace = self.api_server.get::<DataNamespace>(&self.name).await?;
+
+        let Some(dir) = &self.output_dir else {
+            return self
+                .output
+                .document(&export(&namespace.name, &namespace.extraction_graphs)?);
+        };
+
+        fs::create_dir_all(dir).wrap_err_with(|| format!("unable to create {}", dir.display()))?;
+
+        self.write(dir, "namespace", &export(&namespace.name, &[])?)?;
+
+        for g in &namespace.extraction_graphs {
+            self.write(dir, &format!("graph-{}", g.name), &graph::export(g)?)?;
+        }
+
+        Ok(())
+    }
+}
+
 /// Get a specific namespace
 #[derive(Debug, Parser, Command)]
 pub struct Get {
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub api_server: client::Client,
 
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub output: output::Format,
 
     /// Name of the namespace
@@ -95,15 +260,19 @@ impl Command for Get {
 
         self.output.item(&namespace)
     }
+
+    fn watchable(&self) -> bool {
+        true
+    }
 }
 
 /// List all namespaces
 #[derive(Debug, Parser, Command)]
 pub struct List {
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub api_server: client::Client,
 
-    #[clap(from_global)]
+    #[clap(flatten)]
     pub output: output::Format,
 }
 
@@ -113,4 +282,8 @@ impl Command for List {
         self.output
             .list(&self.api_server.list::<Vec<DataNamespace>>().await?)
     }
+
+    fn watchable(&self) -> bool {
+        true
+    }
 }
@@ -1,3 +1,5 @@
+use std::{path::PathBuf, time::Duration};
+
 use clap::{ArgAction, Parser, Subcommand};
 use clap_verbosity_flag::Verbosity;
 use eyre::Result;
@@ -6,7 +8,7 @@ use tracing_log::AsTrace;
 use tracing_subscriber::{filter::EnvFilter, prelude::*};
 
 use crate::{
-    cli::{content, extractor, graph, index, namespace},
+    cli::{apply, config, content, diff, extractor, graph, index, namespace, task},
     client,
     command::Command,
     derive::Command,
@@ -29,14 +31,59 @@ pub struct Root {
         long,
         global = true,
         default_value = client::DEFAULT_SERVICE_URL,
-        env = "INDEXIFY_API_SERVER",
-        requires = "namespace"
+        env = "INDEXIFY_API_SERVER"
     )]
     pub api_server: client::Client,
 
-    /// Output format
-    #[arg(short, long, value_enum, default_value_t = output::Format::Pretty, global = true)]
-    pub output: output::Format,
+    /// Output format: pretty, wide, json, yaml, jsonl, csv, tsv, name,
+    /// custom-columns=NAME:.path,... or jsonpath=TEMPLATE
+    #[arg(
+        short,
+        long,
+        default_value_t = output::Kind::Pretty,
+        global = true,
+        env = "INDEXIFY_OUTPUT"
+    )]
+    pub output: output::Kind,
+
+    /// Columns of tables to show, fields that are hidden by default can be
+    /// picked too
+    #[arg(long, global = true, value_delimiter = ',', help_heading = "Output")]
+    pub columns: Vec<String>,
+
+    /// Sort lists by a column or field, or by a nested field with .path.to.field
+    #[arg(long, global = true, help_heading = "Output")]
+    pub sort_by: Option<String>,
+
+    /// Reverse the order of lists
+    #[arg(long, global = true, help_heading = "Output")]
+    pub reverse: bool,
+
+    /// How times are shown in tables
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = output::tabled::TimeFormat::Relative,
+        env = "INDEXIFY_TIME_FORMAT",
+        help_heading = "Output"
+    )]
+    pub time_format: output::tabled::TimeFormat,
+
+    /// Keep polling a list or get and redraw it, highlighting rows that
+    /// appeared or changed. Every 2s unless an interval is given with
+    /// --watch=INTERVAL
+    #[arg(
+        long,
+        global = true,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "2s",
+        value_name = "INTERVAL",
+        value_parser = humantime::parse_duration,
+        help_heading = "Output"
+    )]
+    pub watch: Option<Duration>,
 
     /// Verbosity level, pass extra v's to increase verbosity
     #[command(flatten)]
@@ -52,6 +99,129 @@ pub struct Root {
     )]
     pub namespace: String,
 
+    /// Path to the config file containing contexts
+    #[arg(long, global = true, env = crate::config::CONFIG_VAR)]
+    pub config: Option<PathBuf>,
+
+    /// Context from the config file to use instead of the current one
+    #[arg(long, global = true, env = crate::config::CONTEXT_VAR)]
+    pub context: Option<String>,
+
+    /// Bearer token sent with every request
+    #[arg(long, global = true, env = "INDEXIFY_TOKEN", hide_env_values = true)]
+    pub token: Option<String>,
+
+    /// Read the bearer token from a file
+    #[arg(long, global = true, env = "INDEXIFY_TOKEN_FILE")]
+    pub token_file: Option<PathBuf>,
+
+    /// Run a command and use its output as the bearer token
+    #[arg(long, global = true, env = "INDEXIFY_TOKEN_COMMAND")]
+    pub token_command: Option<String>,
+
+    /// API key sent with every request, see --api-key-header
+    #[arg(long, global = true, env = "INDEXIFY_API_KEY", hide_env_values = true)]
+    pub api_key: Option<String>,
+
+    /// Header used to send the API key
+    #[arg(
+        long,
+        global = true,
+        default_value = client::auth::DEFAULT_API_KEY_HEADER,
+        env = "INDEXIFY_API_KEY_HEADER"
+    )]
+    pub api_key_header: String,
+
+    /// Timeout for establishing a connection, e.g. 10s
+    #[arg(
+        long,
+        global = true,
+        default_value = "10s",
+        env = "INDEXIFY_CONNECT_TIMEOUT",
+        value_parser = humantime::parse_duration,
+        help_heading = "Connection"
+    )]
+    pub connect_timeout: Duration,
+
+    /// Timeout for reading a response, unlimited by default
+    #[arg(
+        long,
+        global = true,
+        env = "INDEXIFY_READ_TIMEOUT",
+        value_parser = humantime::parse_duration,
+        help_heading = "Connection"
+    )]
+    pub read_timeout: Option<Duration>,
+
+    /// PEM bundle of root certificates to trust in addition to the system ones
+    #[arg(
+        long,
+        global = true,
+        env = "INDEXIFY_CA_FILE",
+        help_heading = "Connection"
+    )]
+    pub ca_file: Option<PathBuf>,
+
+    /// PEM certificate used to authenticate with mTLS
+    #[arg(
+        long,
+        global = true,
+        env = "INDEXIFY_CLIENT_CERT",
+        requires = "client_key",
+        help_heading = "Connection"
+    )]
+    pub client_cert: Option<PathBuf>,
+
+    /// PKCS#8 PEM key for --client-cert
+    #[arg(
+        long,
+        global = true,
+        env = "INDEXIFY_CLIENT_KEY",
+        requires = "client_cert",
+        help_heading = "Connection"
+    )]
+    pub client_key: Option<PathBuf>,
+
+    /// Number of times to retry idempotent requests that fail with a transient
+    /// error
+    #[arg(
+        long,
+        global = true,
+        default_value_t = 3,
+        env = "INDEXIFY_RETRIES",
+        help_heading = "Connection"
+    )]
+    pub retries: u32,
+
+    /// Delay before the first retry, doubled on every attempt after that
+    #[arg(
+        long,
+        global = true,
+        default_value = "250ms",
+        env = "INDEXIFY_RETRY_BACKOFF",
+        value_parser = humantime::parse_duration,
+        help_heading = "Connection"
+    )]
+    pub retry_backoff: Duration,
+
+    /// Send all requests through this proxy
+    #[arg(
+        long,
+        global = true,
+        env = "INDEXIFY_PROXY",
+        help_heading = "Connection"
+    )]
+    pub proxy: Option<String>,
+
+    /// Skip verification of the server's TLS certificate
+    #[arg(
+        long,
+        global = true,
+        env = "INDEXIFY_INSECURE",
+        help_heading = "Connection"
+    )]
+    pub insecure: bool,
+
     /// Enable or disable telemetry
     #[arg(
         long,
@@ -68,17 +238,23 @@ pub struct Root {
 
 #[derive(Debug, Subcommand, Command)]
 pub enum RootCmd {
+    Apply(apply::Apply),
+    Config(config::Config),
     Content(content::Content),
+    Diff(diff::Diff),
     Extractor(extractor::Extractor),
     Graph(graph::Graph),
     Index(index::Index),
     Namespace(namespace::Namespace),
+    Task(task::Task),
 }
 
 impl Command for Root {
     fn pre_run(&self) -> Result<()> {
         let ph = Posthog::new(PH_KEY.unwrap_or("unimplemented"));
 
+        output::tabled::set_time_format(self.time_format);
+
         let filter = EnvFilter::builder()
             .with_default_directive(self.verbosity.log_level_filter().as_trace().into())
             .from_env_lossy();
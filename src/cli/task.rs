@@ -0,0 +1,131 @@
+use clap::{Parser, Subcommand};
+use eyre::Result;
+use tracing::instrument;
+
+use crate::{api, client, command::Command, derive::Command, output};
+
+/// Examine the extraction tasks run on content
+#[derive(Debug, Parser, Command)]
+pub struct Task {
+    #[command(subcommand)]
+    pub cmd: TaskCmd,
+}
+
+#[derive(Debug, Subcommand, Command)]
+pub enum TaskCmd {
+    Get(Get),
+    List(List),
+}
+
+impl Command for Task {}
+
+/// Get a task by its ID
+#[derive(Debug, Parser, Command)]
+pub struct Get {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
+    #[clap(from_global)]
+    pub namespace: String,
+
+    /// ID of the task
+    pub id: String,
+}
+
+#[async_trait::async_trait]
+impl Command for Get {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let task: api::Task = self
+            .api_server
+            .clone()
+            .with_namespace(&self.namespace)
+            .get(&self.id)
+            .await?;
+
+        self.output.item(&task)
+    }
+
+    fn watchable(&self) -> bool {
+        true
+    }
+}
+
+/// List the tasks in a namespace, such as the ones that failed
+#[derive(Debug, Parser, Command)]
+pub struct List {
+    #[clap(flatten)]
+    pub api_server: client::Client,
+
+    #[clap(flatten)]
+    pub output: output::Format,
+
+    #[clap(from_global)]
+    pub namespace: String,
+
+    /// Only show tasks of this extraction policy
+    #[clap(long)]
+    pub policy: Option<String>,
+
+    /// Only show tasks extracting from this content
+    #[clap(long)]
+    pub content_id: Option<String>,
+
+    /// Only show tasks with this outcome: unknown, success or failed
+    #[clap(long)]
+    pub outcome: Option<api::TaskOutcome>,
+
+    /// Maximum number of items to return, the page size with --all
+    #[clap(long)]
+    pub limit: Option<u64>,
+
+    /// Start listing after this task ID
+    #[clap(long)]
+    pub start_id: Option<String>,
+
+    /// Fetch every page
+    #[clap(long)]
+    pub all: bool,
+}
+
+#[async_trait::async_trait]
+impl Command for List {
+    #[instrument]
+    async fn run(&self) -> Result<()> {
+        let client = self.api_server.clone().with_namespace(&self.namespace);
+
+        let query = api::ListTasks {
+            extraction_policy: self.policy.clone(),
+            content_id: self.content_id.clone(),
+            outcome: self.outcome,
+            start_id: self.start_id.clone(),
+            limit: client::page_limit(self.limit, self.all),
+            return_total: true,
+        };
+
+        if self.all {
+            let tasks = client.list_all::<Vec<api::Task>>(query).await?;
+
+            return self.output.list(&tasks);
+        }
+
+        let page = client.list_page::<Vec<api::Task>>(&query).await?;
+
+        self.output.list(&page.items)?;
+
+        self.output.next_page(
+            page.items.len(),
+            page.total,
+            page.next.and_then(|q| q.start_id),
+        );
+
+        Ok(())
+    }
+
+    fn watchable(&self) -> bool {
+        true
+    }
+}
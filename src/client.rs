@@ -1,39 +1,54 @@
+pub mod auth;
+mod builder;
 mod prelude;
+pub mod retry;
 pub mod traits;
 pub mod types;
 
 pub const DEFAULT_SERVICE_URL: &str = "http://localhost:8900";
 
-use std::{any::type_name, fmt::Debug, vec};
+use std::{any::type_name, fmt::Debug, future::Future, path::PathBuf, time::Duration, vec};
 
 use clap::{
     builder::{TypedValueParser, ValueParserFactory},
     error::ErrorKind,
+    parser::ValueSource,
+    ArgMatches, FromArgMatches,
 };
 use color_eyre::{Section, SectionExt};
 use eyre::{eyre, Ok, Result};
 use futures::TryStreamExt;
+use reqwest::{
+    header::{CONTENT_RANGE, RANGE},
+    StatusCode,
+};
 use serde::{de::DeserializeOwned, Serialize};
 use tokio_util::compat::FuturesAsyncReadCompatExt;
-use tracing::instrument;
+use tracing::{info, instrument};
 
-use crate::client::traits::*;
+pub use crate::client::builder::Builder;
+use crate::client::{auth::Auth, retry::Retry, traits::*};
 
+// The underlying reqwest::Client is reference counted, clones share the same
+// connection pool.
 #[derive(Debug, Clone)]
 pub struct Client {
     service_url: reqwest::Url,
     namespace: Option<String>,
+    auth: Option<Auth>,
+    http: reqwest::Client,
+    retry: Retry,
+    idempotent: bool,
 }
 
 // TODO: fold namespaced client into this
 impl Client {
     pub fn new(service_url: &str) -> Result<Self> {
-        let url = reqwest::Url::parse(service_url)?;
+        Self::builder(service_url).build()
+    }
 
-        Ok(Self {
-            service_url: url,
-            namespace: None,
-        })
+    pub fn builder(service_url: &str) -> Builder {
+        Builder::new(service_url)
     }
 
     pub fn with_namespace(mut self, namespace: &str) -> Self {
@@ -41,6 +56,22 @@ impl Client {
         self
     }
 
+    /// Allow `create` and `upload` to be retried. Only use this when sending
+    /// the same request multiple times is safe.
+    pub fn idempotent(mut self) -> Self {
+        self.idempotent = true;
+        self
+    }
+
+    fn request(&self, method: reqwest::Method, url: reqwest::Url) -> reqwest::RequestBuilder {
+        let req = self.http.request(method, url);
+
+        match &self.auth {
+            Some(auth) => auth.apply(req),
+            None => req,
+        }
+    }
+
     fn url<T>(&self, id: Option<&str>) -> Result<reqwest::Url>
     where
         T: Namespaced,
@@ -70,20 +101,60 @@ impl Client {
         Ok(url)
     }
 
-    #[instrument(level = "trace")]
-    async fn _get(&self, path: reqwest::Url) -> Result<String> {
-        reqwest::get(path.as_str()).await?.text_or_error().await
+    // Requests are rebuilt for every attempt as streaming bodies (such as
+    // uploads) can't be cloned.
+    async fn send<F, Fut>(&self, idempotent: bool, req: F) -> Result<reqwest::Response>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<reqwest::RequestBuilder>>,
+    {
+        let mut attempt = 1;
+
+        loop {
+            let result = req().await?.send().await;
+
+            if !(idempotent || self.idempotent) {
+                return Ok(result?);
+            }
+
+            let Some(delay) = self.retry.delay(attempt, &result) else {
+                return Ok(result?);
+            };
+
+            let reason = match &result {
+                std::result::Result::Ok(resp) => resp.status().to_string(),
+                Err(e) => e.to_string(),
+            };
+
+            info!(attempt, ?delay, reason, "retrying request");
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    #[instrument(level = "trace", skip(query))]
+    async fn _get(&self, path: reqwest::Url, query: &(impl Serialize + Sync)) -> Result<String> {
+        self.send(true, || async {
+            Ok(self
+                .request(reqwest::Method::GET, path.clone())
+                .query(query))
+        })
+        .await?
+        .text_or_error()
+        .await
     }
 
     #[instrument(level = "trace", skip(body))]
     async fn _post(&self, path: reqwest::Url, body: impl Serialize) -> Result<String> {
-        reqwest::Client::new()
-            .post(path.as_str())
-            .json(&body)
-            .send()
-            .await?
-            .text_or_error()
-            .await
+        self.send(false, || async {
+            Ok(self
+                .request(reqwest::Method::POST, path.clone())
+                .json(&body))
+        })
+        .await?
+        .text_or_error()
+        .await
     }
 
     fn deserialize<T>(&self, resp: &str) -> Result<T>
@@ -100,11 +171,46 @@ impl Client {
     where
         T: List,
         T::Response: Into<T>,
-        T::Item: Namespaced,
     {
-        let resp = self._get(self.url::<T::Item>(None)?).await?;
+        Ok(self.list_page::<T>(&T::Query::default()).await?.items)
+    }
 
-        Ok(self.deserialize::<T::Response>(&resp)?.into())
+    /// A single page of items, along with the query for the next one.
+    pub async fn list_page<T>(&self, query: &T::Query) -> Result<Page<T>>
+    where
+        T: List,
+        T::Response: Into<T>,
+    {
+        let resp = self._get(self.url::<T::Item>(None)?, query).await?;
+        let resp = self.deserialize::<T::Response>(&resp)?;
+
+        Ok(Page {
+            total: T::total(&resp),
+            next: T::next(query, &resp),
+            items: resp.into(),
+        })
+    }
+
+    /// Every item matching the query, fetched a page at a time.
+    pub async fn list_all<T>(&self, query: T::Query) -> Result<Vec<<T as List>::Item>>
+    where
+        T: List + IntoIterator<Item = <T as List>::Item>,
+        T::Response: Into<T>,
+        <T as List>::Item: PartialEq,
+    {
+        let mut items = vec![];
+        let mut query = Some(query);
+
+        while let Some(current) = query {
+            let page = self.list_page::<T>(&current).await?;
+
+            query = page.next;
+            if !append(&mut items, page.items) {
+                break;
+            }
+        }
+
+        Ok(items)
     }
 
     pub async fn get<T>(&self, id: &str) -> Result<T>
@@ -112,27 +218,48 @@ impl Client {
         T: Get,
         T::Response: Into<T>,
     {
-        let resp = self._get(self.url::<T>(Some(id))?).await?;
+        let resp = self._get(self.url::<T>(Some(id))?, &()).await?;
 
         Ok(self.deserialize::<T::Response>(&resp)?.into())
     }
 
+    /// Stream the body of `T`, starting `offset` bytes in. Servers that don't
+    /// support range requests send the whole body, check `Stream::offset`.
     pub async fn get_stream<T>(
         &self,
         id: Option<&str>,
-    ) -> Result<(Option<u64>, impl tokio::io::AsyncRead)>
+        offset: u64,
+    ) -> Result<Stream<impl tokio::io::AsyncRead>>
     where
         T: Namespaced,
     {
-        let resp = reqwest::get(self.url::<T>(id)?).await?.error_for_status()?;
-
-        Ok((
-            resp.content_length(),
-            resp.bytes_stream()
-                .map_err(|e| futures::io::Error::new(futures::io::ErrorKind::Other, e))
+        let url = self.url::<T>(id)?;
+        let resp = self
+            .send(true, || async {
+                let req = self.request(reqwest::Method::GET, url.clone());
+
+                Ok(match offset {
+                    0 => req,
+                    _ => req.header(RANGE, format!("bytes={}-", offset)),
+                })
+            })
+            .await?
+            .error_for_status()?;
+
+        let (offset, size) = match resp.status() {
+            StatusCode::PARTIAL_CONTENT => (offset, content_range_size(&resp)),
+            _ => (0, resp.content_length()),
+        };
+
+        Ok(Stream {
+            offset,
+            size,
+            reader: resp
+                .bytes_stream()
+                .map_err(futures::io::Error::other)
                 .into_async_read()
                 .compat(),
-        ))
+        })
     }
 
     pub async fn create<T>(&self, obj: &T) -> Result<T::Response>
@@ -144,15 +271,53 @@ impl Client {
         Ok(self.deserialize::<T::Response>(&resp)?)
     }
 
+    /// Delete the object with the given ID, anything the server responds with
+    /// is ignored.
+    pub async fn delete_by_id<T>(&self, id: &str) -> Result<()>
+    where
+        T: Namespaced,
+    {
+        let url = self.url::<T>(Some(id))?;
+
+        self.send(true, || async {
+            Ok(self.request(reqwest::Method::DELETE, url.clone()))
+        })
+        .await?
+        .text_or_error()
+        .await?;
+
+        Ok(())
+    }
+
+    /// Unlike `create`, queries don't change anything and are always retried.
+    pub async fn query<T>(&self, body: &T) -> Result<T::Response>
+    where
+        T: Query,
+    {
+        let url = self.url::<T>(None)?;
+        let resp = self
+            .send(true, || async {
+                Ok(self.request(reqwest::Method::POST, url.clone()).json(body))
+            })
+            .await?
+            .text_or_error()
+            .await?;
+
+        self.deserialize(&resp)
+    }
+
     pub async fn delete<T>(&self, body: T) -> Result<T::Response>
     where
         T: Delete,
         T::Response: DeserializeOwned,
     {
-        let resp = reqwest::Client::new()
-            .delete(self.url::<T>(None)?)
-            .json(&body)
-            .send()
+        let url = self.url::<T>(None)?;
+        let resp = self
+            .send(true, || async {
+                Ok(self
+                    .request(reqwest::Method::DELETE, url.clone())
+                    .json(&body))
+            })
             .await?
             .text_or_error()
             .await?;
@@ -160,21 +325,50 @@ impl Client {
         self.deserialize(&resp)
     }
 
-    // TODO: figure out how to do progress bars from streams
-    pub async fn upload<T>(&self, content: T) -> Result<()>
+    /// Replace the object with the given ID, sent as a PUT.
+    pub async fn update<T>(&self, id: &str, body: &T) -> Result<T::Response>
+    where
+        T: Update,
+    {
+        let url = self.url::<T>(Some(id))?;
+        let resp = self
+            .send(true, || async {
+                Ok(self.request(reqwest::Method::PUT, url.clone()).json(body))
+            })
+            .await?
+            .text_or_error()
+            .await?;
+
+        if resp.trim().is_empty() {
+            return self.deserialize("{}");
+        }
+
+        self.deserialize(&resp)
+    }
+
+    pub async fn upload<T>(&self, content: T) -> Result<T::Response>
     where
         T: Upload + Namespaced,
     {
-        reqwest::Client::new()
-            .post(self.url::<T>(None)?)
-            .multipart(content.form().await?)
-            .query(&content.query())
-            .send()
+        let url = self.url::<T>(None)?;
+
+        let resp = self
+            .send(false, || async {
+                Ok(self
+                    .request(reqwest::Method::POST, url.clone())
+                    .multipart(content.form().await?)
+                    .query(&content.query()))
+            })
             .await?
             .text_or_error()
             .await?;
 
-        Ok(())
+        // Older servers respond with an empty body.
+        if resp.trim().is_empty() {
+            return self.deserialize("{}");
+        }
+
+        self.deserialize(&resp)
     }
 }
 
@@ -183,10 +377,91 @@ impl Default for Client {
         Self {
             service_url: reqwest::Url::parse(DEFAULT_SERVICE_URL).unwrap(),
             namespace: Some("default".to_string()),
+            auth: None,
+            http: reqwest::Client::new(),
+            retry: Retry::default(),
+            idempotent: false,
         }
     }
 }
 
+/// Page size when fetching every item.
+pub const PAGE_SIZE: u64 = 100;
+
+/// The limit to ask for, pages default to `PAGE_SIZE` when fetching every item
+/// and to the server's limit otherwise.
+pub fn page_limit(limit: Option<u64>, all: bool) -> Option<u64> {
+    match all {
+        true => limit.or(Some(PAGE_SIZE)),
+        false => limit,
+    }
+}
+
+pub struct Page<T>
+where
+    T: List,
+{
+    pub items: T,
+    pub total: Option<u64>,
+    pub next: Option<T::Query>,
+}
+
+pub struct Stream<R> {
+    /// Where in the body the reader starts.
+    pub offset: u64,
+    /// Size of the whole body, if the server sent it.
+    pub size: Option<u64>,
+    pub reader: R,
+}
+
+// `Content-Range: bytes 100-999/1000`, the total can be `*` if unknown.
+fn content_range_size(resp: &reqwest::Response) -> Option<u64> {
+    resp.headers()
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit_once('/')?
+        .1
+        .parse()
+        .ok()
+}
+
+// Servers that include `start_id` in the next page repeat its first item, skip
+// it. False when the page added nothing, so the caller stops instead of
+// asking for it again.
+fn append<T: PartialEq>(items: &mut Vec<T>, page: impl IntoIterator<Item = T>) -> bool {
+    let mut page = page.into_iter().peekable();
+    if items.last().is_some() && items.last() == page.peek() {
+        page.next();
+    }
+
+    let len = items.len();
+    items.extend(page);
+
+    items.len() > len
+}
+
+/// The server answered with an error status. Callers that expect one, such as
+/// a 404 for something that doesn't exist yet, can downcast to it.
+#[derive(Debug)]
+pub struct Status {
+    pub status: StatusCode,
+    pub url: reqwest::Url,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} for {}", self.status, self.url)
+    }
+}
+
+impl std::error::Error for Status {}
+
+pub fn is_not_found(err: &eyre::Report) -> bool {
+    err.downcast_ref::<Status>()
+        .is_some_and(|s| s.status == StatusCode::NOT_FOUND)
+}
+
 trait WithBody {
     async fn text_or_error(self) -> Result<String>;
 }
@@ -198,7 +473,8 @@ impl WithBody for reqwest::Response {
         let out = self.text().await?;
 
         if !status.is_success() {
-            return Err(eyre!("{} for {}", status, url)).with_section(move || out.header("Body:"));
+            return Err(eyre::Report::new(Status { status, url }))
+                .with_section(move || out.header("Body:"));
         }
 
         Ok(out)
@@ -237,3 +513,266 @@ impl ValueParserFactory for Client {
         Self::default()
     }
 }
+
+// Commands flatten `Client` in instead of using `from_global` so that the
+// credential flags defined on `cli::root::Root` can be folded into the client
+// parsed from `--api-server`. The IDs here must match the field names on Root.
+impl FromArgMatches for Client {
+    fn from_arg_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+        let url = global::<Client>(matches, "api_server")
+            .unwrap_or_default()
+            .service_url
+            .to_string();
+
+        let mut builder =
+            Client::builder(&url).insecure(global::<bool>(matches, "insecure").unwrap_or_default());
+
+        match auth_id(matches)? {
+            Some("token") => {
+                builder = builder.auth(Auth::bearer(&global::<String>(matches, "token").unwrap()));
+            }
+            Some("token_file") => {
+                let path = global::<PathBuf>(matches, "token_file").unwrap();
+                builder = builder.auth(Auth::from_file(&path).map_err(invalid_value)?);
+            }
+            Some("token_command") => {
+                let cmd = global::<String>(matches, "token_command").unwrap();
+                builder = builder.auth(Auth::from_command(&cmd).map_err(invalid_value)?);
+            }
+            Some("api_key") => {
+                let header = global::<String>(matches, "api_key_header")
+                    .unwrap_or_else(|| auth::DEFAULT_API_KEY_HEADER.to_string());
+                let key = global::<String>(matches, "api_key").unwrap();
+
+                builder = builder.auth(Auth::api_key(&header, &key));
+            }
+            _ => {}
+        }
+
+        if let Some(timeout) = global::<Duration>(matches, "connect_timeout") {
+            builder = builder.connect_timeout(timeout);
+        }
+
+        if let Some(timeout) = global::<Duration>(matches, "read_timeout") {
+            builder = builder.read_timeout(timeout);
+        }
+
+        if let Some(path) = global::<PathBuf>(matches, "ca_file") {
+            builder = builder.ca_file(path);
+        }
+
+        if let (Some(cert), Some(key)) = (
+            global::<PathBuf>(matches, "client_cert"),
+            global::<PathBuf>(matches, "client_key"),
+        ) {
+            builder = builder.identity(cert, key);
+        }
+
+        if let Some(retries) = global::<u32>(matches, "retries") {
+            let mut retry = Retry::default().with_max_attempts(retries + 1);
+
+            if let Some(backoff) = global::<Duration>(matches, "retry_backoff") {
+                retry = retry.with_backoff(backoff);
+            }
+
+            builder = builder.retry(retry);
+        }
+
+        if let Some(proxy) = global::<String>(matches, "proxy") {
+            builder = builder.proxy(&proxy);
+        }
+
+        builder.build().map_err(invalid_value)
+    }
+
+    fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), clap::Error> {
+        *self = Self::from_arg_matches(matches)?;
+
+        std::result::Result::Ok(())
+    }
+}
+
+// All the arguments are globals defined on Root, there's nothing to add.
+impl clap::Args for Client {
+    fn augment_args(cmd: clap::Command) -> clap::Command {
+        cmd
+    }
+
+    fn augment_args_for_update(cmd: clap::Command) -> clap::Command {
+        cmd
+    }
+}
+
+// Credentials passed as flags win over ones from the environment (or the
+// active context), regardless of the kind of credential. Two from the same
+// place can't be told apart, so they're reported instead of picking one.
+fn auth_id(matches: &ArgMatches) -> Result<Option<&'static str>, clap::Error> {
+    let ids = ["token", "token_file", "token_command", "api_key"];
+    let from = |source: ValueSource| -> Vec<&'static str> {
+        ids.into_iter()
+            .filter(|id| matches.value_source(id) == Some(source))
+            .collect()
+    };
+
+    let flags = from(ValueSource::CommandLine);
+    let vars = from(ValueSource::EnvVariable);
+
+    let message = match (flags.as_slice(), vars.as_slice()) {
+        ([id], _) | ([], [id]) => return std::result::Result::Ok(Some(id)),
+        ([], []) => return std::result::Result::Ok(None),
+        ([], ids) => format!(
+            "only one of {} can be set",
+            ids.iter()
+                .map(|id| format!("INDEXIFY_{}", id.to_uppercase()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        (ids, _) => format!(
+            "only one of {} can be used",
+            ids.iter()
+                .map(|id| format!("--{}", id.replace('_', "-")))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+
+    Err(clap::Error::raw(ErrorKind::ArgumentConflict, message))
+}
+
+fn global<T>(matches: &ArgMatches, id: &str) -> Option<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    matches.get_one::<T>(id).cloned()
+}
+
+fn invalid_value(err: eyre::Report) -> clap::Error {
+    clap::Error::raw(ErrorKind::InvalidValue, format!("{:#}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth(args: &[&str]) -> Result<Option<&'static str>, clap::Error> {
+        let matches = <crate::cli::root::Root as clap::CommandFactory>::command()
+            .try_get_matches_from(
+                ["indexify"]
+                    .iter()
+                    .chain(args)
+                    .chain(&["namespace", "list"]),
+            )?;
+
+        auth_id(&matches)
+    }
+
+    #[test]
+    fn auth_from_flags() {
+        assert_eq!(auth(&["--token", "t"]).unwrap(), Some("token"));
+        assert_eq!(auth(&["--api-key", "k"]).unwrap(), Some("api_key"));
+    }
+
+    #[test]
+    fn auth_flags_conflict() {
+        let err = auth(&["--token", "t", "--api-key", "k"]).unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+        assert!(err.to_string().contains("--token, --api-key"));
+    }
+
+    // The only test that sets a credential variable, tests share the
+    // environment.
+    #[test]
+    fn auth_flags_win_over_the_environment() {
+        std::env::set_var("INDEXIFY_TOKEN_COMMAND", "echo t");
+
+        assert_eq!(auth(&[]).unwrap(), Some("token_command"));
+        assert_eq!(auth(&["--api-key", "k"]).unwrap(), Some("api_key"));
+
+        std::env::remove_var("INDEXIFY_TOKEN_COMMAND");
+    }
+
+    #[test]
+    fn page_limit_defaults_with_all() {
+        assert_eq!(page_limit(None, true), Some(PAGE_SIZE));
+        assert_eq!(page_limit(Some(10), true), Some(10));
+        assert_eq!(page_limit(None, false), None);
+        assert_eq!(page_limit(Some(10), false), Some(10));
+    }
+
+    #[test]
+    fn append_pages() {
+        let mut items = vec![1, 2];
+
+        assert!(append(&mut items, vec![3, 4]));
+        assert_eq!(items, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn append_skips_repeated_first_item() {
+        let mut items = vec![1, 2];
+
+        assert!(append(&mut items, vec![2, 3]));
+        assert_eq!(items, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn append_stops_without_new_items() {
+        let mut items = vec![1, 2];
+
+        assert!(!append(&mut items, vec![2]));
+        assert!(!append(&mut items, vec![]));
+        assert_eq!(items, vec![1, 2]);
+    }
+
+    // Answers every request with `status` and an empty JSON object.
+    async fn serve(status: &'static str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            while let std::result::Result::Ok((mut socket, _)) = listener.accept().await {
+                let mut request = vec![];
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+
+                let head = format!(
+                    "HTTP/1.1 {}\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{{}}",
+                    status
+                );
+                socket.write_all(head.as_bytes()).await.unwrap();
+            }
+        });
+
+        addr
+    }
+
+    #[tokio::test]
+    async fn not_found_status() {
+        let client = Client::new(&serve("404 Not Found").await).unwrap();
+        let err = client
+            .get::<crate::api::DataNamespace>("missing")
+            .await
+            .unwrap_err();
+
+        assert!(is_not_found(&err));
+        assert!(err.to_string().starts_with("404 Not Found for "));
+    }
+
+    #[tokio::test]
+    async fn other_statuses_are_not_not_found() {
+        let client = Client::new(&serve("400 Bad Request").await).unwrap();
+        let err = client
+            .get::<crate::api::DataNamespace>("bad")
+            .await
+            .unwrap_err();
+
+        assert!(!is_not_found(&err));
+        assert!(!is_not_found(&eyre!("404 Not Found")));
+    }
+}
@@ -0,0 +1,66 @@
+use std::{fmt, path::Path, process};
+
+use eyre::{eyre, Result, WrapErr};
+
+pub const DEFAULT_API_KEY_HEADER: &str = "X-API-Key";
+
+/// Credentials attached to every request made by `Client`.
+#[derive(Clone)]
+pub enum Auth {
+    Bearer(String),
+    ApiKey { header: String, key: String },
+}
+
+impl Auth {
+    pub fn bearer(token: &str) -> Self {
+        Self::Bearer(token.trim().to_string())
+    }
+
+    pub fn api_key(header: &str, key: &str) -> Self {
+        Self::ApiKey {
+            header: header.to_string(),
+            key: key.trim().to_string(),
+        }
+    }
+
+    /// Read a bearer token from the contents of a file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let token = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("unable to read token from {}", path.display()))?;
+
+        Ok(Self::bearer(&token))
+    }
+
+    /// Run a shell command and use its stdout as a bearer token.
+    pub fn from_command(cmd: &str) -> Result<Self> {
+        let output = process::Command::new("sh")
+            .args(["-c", cmd])
+            .stderr(process::Stdio::inherit())
+            .output()
+            .wrap_err_with(|| format!("unable to run token command: {}", cmd))?;
+
+        if !output.status.success() {
+            return Err(eyre!("token command failed ({}): {}", output.status, cmd));
+        }
+
+        Ok(Self::bearer(&String::from_utf8(output.stdout)?))
+    }
+
+    pub fn apply(&self, req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self {
+            Self::Bearer(token) => req.bearer_auth(token),
+            Self::ApiKey { header, key } => req.header(header.as_str(), key.as_str()),
+        }
+    }
+}
+
+// Credentials end up in tracing spans via `#[instrument]`, keep them out of the
+// logs and telemetry.
+impl fmt::Debug for Auth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bearer(_) => write!(f, "Bearer(<redacted>)"),
+            Self::ApiKey { header, .. } => write!(f, "ApiKey({}: <redacted>)", header),
+        }
+    }
+}
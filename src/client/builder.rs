@@ -0,0 +1,127 @@
+use std::{path::PathBuf, time::Duration};
+
+use eyre::{Result, WrapErr};
+
+use crate::client::{auth::Auth, retry::Retry, Client};
+
+static USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Configures the HTTP transport shared by every request a `Client` makes.
+#[derive(Debug, Clone, Default)]
+pub struct Builder {
+    service_url: String,
+    auth: Option<Auth>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    ca_file: Option<PathBuf>,
+    identity: Option<(PathBuf, PathBuf)>,
+    proxy: Option<String>,
+    insecure: bool,
+    retry: Retry,
+}
+
+impl Builder {
+    pub fn new(service_url: &str) -> Self {
+        Self {
+            service_url: service_url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// PEM bundle of additional root certificates to trust.
+    pub fn ca_file(mut self, path: PathBuf) -> Self {
+        self.ca_file = Some(path);
+        self
+    }
+
+    /// PEM encoded certificate and PKCS#8 key used for mTLS.
+    pub fn identity(mut self, cert: PathBuf, key: PathBuf) -> Self {
+        self.identity = Some((cert, key));
+        self
+    }
+
+    /// Proxy all requests through this URL.
+    pub fn proxy(mut self, url: &str) -> Self {
+        self.proxy = Some(url.to_string());
+        self
+    }
+
+    pub fn retry(mut self, retry: Retry) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Skip TLS certificate verification.
+    pub fn insecure(mut self, insecure: bool) -> Self {
+        self.insecure = insecure;
+        self
+    }
+
+    pub fn build(self) -> Result<Client> {
+        let service_url = reqwest::Url::parse(&self.service_url)?;
+
+        let mut http = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .danger_accept_invalid_certs(self.insecure);
+
+        if let Some(timeout) = self.connect_timeout {
+            http = http.connect_timeout(timeout);
+        }
+
+        if let Some(timeout) = self.read_timeout {
+            http = http.read_timeout(timeout);
+        }
+
+        if let Some(path) = self.ca_file {
+            let pem = std::fs::read(&path)
+                .wrap_err_with(|| format!("unable to read CA bundle {}", path.display()))?;
+
+            for cert in reqwest::Certificate::from_pem_bundle(&pem)
+                .wrap_err_with(|| format!("invalid CA bundle {}", path.display()))?
+            {
+                http = http.add_root_certificate(cert);
+            }
+        }
+
+        if let Some((cert, key)) = self.identity {
+            let cert = std::fs::read(&cert).wrap_err_with(|| {
+                format!("unable to read client certificate {}", cert.display())
+            })?;
+            let key = std::fs::read(&key)
+                .wrap_err_with(|| format!("unable to read client key {}", key.display()))?;
+
+            http = http.identity(
+                reqwest::Identity::from_pkcs8_pem(&cert, &key)
+                    .wrap_err("invalid client certificate or key")?,
+            );
+        }
+
+        if let Some(proxy) = self.proxy {
+            http = http.proxy(reqwest::Proxy::all(&proxy)?);
+        }
+
+        Ok(Client {
+            service_url,
+            namespace: None,
+            auth: self.auth,
+            http: http.build()?,
+            retry: self.retry,
+            idempotent: false,
+        })
+    }
+}
@@ -14,6 +14,7 @@ impl From<ListNamespacesResponse> for Vec<DataNamespace> {
 
 impl List for Vec<DataNamespace> {
     type Item = DataNamespace;
+    type Query = ();
     type Response = ListNamespacesResponse;
 }
 
@@ -52,6 +53,10 @@ impl Create for CreateNamespace {
 }
 
 impl Namespaced for CreateNamespace {
+    fn is_namespaced() -> bool {
+        false
+    }
+
     fn segments(_: Option<&str>) -> Result<Vec<&str>> {
         Ok(vec!["namespaces"])
     }
@@ -65,6 +70,7 @@ impl From<ListExtractorsResponse> for Vec<ExtractorDescription> {
 
 impl List for Vec<ExtractorDescription> {
     type Item = ExtractorDescription;
+    type Query = ();
     type Response = ListExtractorsResponse;
 }
 
@@ -81,11 +87,7 @@ impl Namespaced for ExtractorDescription {
 impl Namespaced for ExtractionGraph {
     fn segments(id: Option<&str>) -> Result<Vec<&str>> {
         match id {
-            // TODO: generate this boilerplate with a proper error
-            Some(_) => Err(eyre!(
-                "{} does not support getting by ID.",
-                type_name::<Self>()
-            )),
+            Some(id) => Ok(vec!["extraction_graphs", id]),
             None => Ok(vec!["extraction_graphs"]),
         }
     }
@@ -103,6 +105,7 @@ impl From<ListIndexesResponse> for Vec<Index> {
 
 impl List for Vec<Index> {
     type Item = Index;
+    type Query = ();
     type Response = ListIndexesResponse;
 }
 
@@ -118,6 +121,16 @@ impl Namespaced for Index {
     }
 }
 
+impl Namespaced for SearchRequest {
+    fn segments(_: Option<&str>) -> Result<Vec<&str>> {
+        Ok(vec!["search"])
+    }
+}
+
+impl Query for SearchRequest {
+    type Response = IndexSearchResponse;
+}
+
 impl From<ListContentResponse> for Vec<ContentMetadata> {
     fn from(resp: ListContentResponse) -> Self {
         resp.content_list
@@ -126,7 +139,28 @@ impl From<ListContentResponse> for Vec<ContentMetadata> {
 
 impl List for Vec<ContentMetadata> {
     type Item = ContentMetadata;
+    type Query = ListContent;
     type Response = ListContentResponse;
+
+    fn total(resp: &Self::Response) -> Option<u64> {
+        Some(resp.total)
+    }
+
+    // Pages are only complete when they're as long as the limit, the next one
+    // starts after the last ID seen.
+    fn next(query: &Self::Query, resp: &Self::Response) -> Option<Self::Query> {
+        let limit = query.limit?;
+        let last = resp.content_list.last()?;
+
+        if (resp.content_list.len() as u64) < limit {
+            return None;
+        }
+
+        Some(ListContent {
+            start_id: Some(last.id.clone()),
+            ..query.clone()
+        })
+    }
 }
 
 impl Namespaced for ContentMetadata {
@@ -147,3 +181,66 @@ impl From<GetContentMetadataResponse> for ContentMetadata {
 impl Get for ContentMetadata {
     type Response = GetContentMetadataResponse;
 }
+
+impl Namespaced for UpdateLabelsRequest {
+    fn segments(id: Option<&str>) -> Result<Vec<&str>> {
+        match id {
+            Some(id) => Ok(vec!["content", id, "labels"]),
+            None => Err(eyre!("Cannot update labels without an ID.")),
+        }
+    }
+}
+
+impl Update for UpdateLabelsRequest {
+    type Response = HashMap<String, String>;
+}
+
+impl From<ListTasksResponse> for Vec<Task> {
+    fn from(resp: ListTasksResponse) -> Self {
+        resp.tasks
+    }
+}
+
+impl List for Vec<Task> {
+    type Item = Task;
+    type Query = ListTasks;
+    type Response = ListTasksResponse;
+
+    fn total(resp: &Self::Response) -> Option<u64> {
+        resp.total
+    }
+
+    // Paged the same way as content.
+    fn next(query: &Self::Query, resp: &Self::Response) -> Option<Self::Query> {
+        let limit = query.limit?;
+        let last = resp.tasks.last()?;
+
+        if (resp.tasks.len() as u64) < limit {
+            return None;
+        }
+
+        Some(ListTasks {
+            start_id: Some(last.id.clone()),
+            ..query.clone()
+        })
+    }
+}
+
+impl Namespaced for Task {
+    fn segments(id: Option<&str>) -> Result<Vec<&str>> {
+        match id {
+            Some(id) => Ok(vec!["tasks", id]),
+            None => Ok(vec!["tasks"]),
+        }
+    }
+}
+
+impl From<GetTaskResponse> for Task {
+    fn from(resp: GetTaskResponse) -> Self {
+        resp.task
+    }
+}
+
+impl Get for Task {
+    type Response = GetTaskResponse;
+}
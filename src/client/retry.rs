@@ -0,0 +1,197 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::{Duration, SystemTime},
+};
+
+use reqwest::{
+    header::{HeaderMap, RETRY_AFTER},
+    Response, StatusCode,
+};
+
+/// How requests are retried when the server is unavailable. Only idempotent
+/// requests are retried unless the client has been marked with
+/// `Client::idempotent`.
+#[derive(Debug, Clone)]
+pub struct Retry {
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled on every attempt after that.
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+    /// Randomize the delay so that many clients don't retry in lockstep.
+    pub jitter: bool,
+    pub statuses: Vec<StatusCode>,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            statuses: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+        }
+    }
+}
+
+impl Retry {
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Returns how long to wait before trying again, or None if the result
+    /// should be returned as is.
+    pub fn delay(&self, attempt: u32, result: &reqwest::Result<Response>) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        match result {
+            Ok(resp) if self.statuses.contains(&resp.status()) => {
+                Some(self.status_delay(attempt, resp.headers()))
+            }
+            Ok(_) => None,
+            // Other errors, such as failing to build the request or read the
+            // body, would fail the same way again.
+            Err(e) if e.is_connect() || e.is_timeout() => Some(self.backoff(attempt)),
+            Err(_) => None,
+        }
+    }
+
+    // Retry-After is honored, but never waits longer than the backoff could.
+    fn status_delay(&self, attempt: u32, headers: &HeaderMap) -> Duration {
+        match retry_after(headers) {
+            Some(delay) => delay.min(self.max_backoff),
+            None => self.backoff(attempt),
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self
+            .backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_backoff);
+
+        if !self.jitter {
+            return delay;
+        }
+
+        // Equal jitter, half the delay is fixed and the other half is random.
+        let random = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+
+        delay / 2 + delay.mul_f64(random / 2.0)
+    }
+}
+
+// Retry-After is either a number of seconds or an HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?;
+
+    match value.parse::<u64>() {
+        Ok(secs) => Some(Duration::from_secs(secs)),
+        Err(_) => httpdate::parse_http_date(value)
+            .ok()?
+            .duration_since(SystemTime::now())
+            .ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn retry() -> Retry {
+        Retry {
+            jitter: false,
+            ..Default::default()
+        }
+    }
+
+    fn headers(retry_after: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, retry_after.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_max() {
+        let retry = retry();
+
+        assert_eq!(retry.backoff(1), Duration::from_millis(250));
+        assert_eq!(retry.backoff(2), Duration::from_millis(500));
+        assert_eq!(retry.backoff(3), Duration::from_secs(1));
+        assert_eq!(retry.backoff(10), Duration::from_secs(10));
+        assert_eq!(retry.backoff(u32::MAX), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn jitter_keeps_at_least_half_the_delay() {
+        let retry = Retry::default();
+
+        for attempt in 1..6 {
+            let delay = retry.backoff(attempt);
+            let full = Retry {
+                jitter: false,
+                ..Default::default()
+            }
+            .backoff(attempt);
+
+            assert!(
+                delay >= full / 2 && delay <= full,
+                "{:?} for {:?}",
+                delay,
+                full
+            );
+        }
+    }
+
+    #[test]
+    fn retry_after_seconds() {
+        assert_eq!(retry_after(&headers("3")), Some(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn retry_after_http_date() {
+        let date = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(60));
+        let delay = retry_after(&headers(&date)).unwrap();
+
+        assert!(delay > Duration::from_secs(55) && delay <= Duration::from_secs(60));
+    }
+
+    #[test]
+    fn retry_after_in_the_past_or_invalid() {
+        let date = httpdate::fmt_http_date(SystemTime::now() - Duration::from_secs(60));
+
+        assert_eq!(retry_after(&headers(&date)), None);
+        assert_eq!(retry_after(&headers("soon")), None);
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn retry_after_is_capped() {
+        let retry = retry();
+
+        assert_eq!(
+            retry.status_delay(1, &headers("86400")),
+            Duration::from_secs(10)
+        );
+        assert_eq!(retry.status_delay(1, &headers("2")), Duration::from_secs(2));
+        assert_eq!(
+            retry.status_delay(2, &HeaderMap::new()),
+            Duration::from_millis(500)
+        );
+    }
+}
@@ -24,9 +24,33 @@ pub trait Get: Namespaced {
 pub trait List {
     type Item: Namespaced;
     type Response: DeserializeOwned;
+    /// Filters and pagination, sent as query parameters. Use `()` when the
+    /// server doesn't support any.
+    type Query: Serialize + Default + Sync;
+
+    /// Total number of items matching the query, when the server reports it.
+    fn total(_: &Self::Response) -> Option<u64> {
+        None
+    }
+
+    /// The query for the page after `resp`, None when it was the last one.
+    fn next(_: &Self::Query, _: &Self::Response) -> Option<Self::Query> {
+        None
+    }
+}
+
+/// Requests that only read data but need a body, so are sent as a POST.
+pub trait Query: Namespaced + Serialize {
+    type Response: DeserializeOwned;
+}
+
+pub trait Update: Namespaced + Serialize {
+    type Response: DeserializeOwned;
 }
 
 pub trait Upload: Namespaced {
+    type Response: DeserializeOwned;
+
     async fn form(&self) -> Result<multipart::Form>;
     fn query(&self) -> impl Serialize;
 }
@@ -1,11 +1,13 @@
 // New types that should probably be part of crate::api
 
-use std::{collections::HashMap, fmt::Debug, path::Path, vec};
+use std::{collections::HashMap, fmt::Debug, path::Path, sync::Mutex, vec};
 
 use eyre::{eyre, Ok, Result};
+use futures::TryStreamExt;
+use indicatif::ProgressBar;
 use reqwest::{multipart, Body};
-use serde::Serialize;
-use tokio::fs::File;
+use serde::{Deserialize, Serialize};
+use tokio::{fs::File, io::AsyncRead};
 use tokio_util::codec::{BytesCodec, FramedRead};
 
 use crate::client::traits::*;
@@ -31,16 +33,49 @@ impl Delete for ContentIds {
     type Response = HashMap<String, String>;
 }
 
+type Reader = Box<dyn AsyncRead + Send + Unpin>;
+
+enum Source<'a> {
+    Path(&'a Path),
+    /// Streamed as it is read, so it can only be sent once.
+    Reader(Mutex<Option<Reader>>),
+}
+
 pub struct ContentUpload<'a> {
-    path: &'a Path,
+    source: Source<'a>,
+    name: Option<String>,
+    mime_type: Option<String>,
     graph_names: Vec<String>,
+    labels: HashMap<String, serde_json::Value>,
+    progress: Option<ProgressBar>,
 }
 
 impl<'a> ContentUpload<'a> {
     pub fn new(path: &'a Path) -> ContentUpload<'a> {
         Self {
-            path,
+            source: Source::Path(path),
+            name: None,
+            mime_type: None,
+            graph_names: vec![],
+            labels: HashMap::new(),
+            progress: None,
+        }
+    }
+
+    /// Upload content as it is read, such as stdin. A name is required as
+    /// there is no path to take it from. Nothing is kept in memory, so the
+    /// upload can't be retried.
+    pub fn from_reader(
+        name: &str,
+        reader: impl AsyncRead + Send + Unpin + 'static,
+    ) -> ContentUpload<'a> {
+        Self {
+            source: Source::Reader(Mutex::new(Some(Box::new(reader)))),
+            name: Some(name.to_string()),
+            mime_type: None,
             graph_names: vec![],
+            labels: HashMap::new(),
+            progress: None,
         }
     }
 
@@ -48,6 +83,39 @@ impl<'a> ContentUpload<'a> {
         self.graph_names = graph_names.to_vec();
         self
     }
+
+    pub fn with_labels(mut self, labels: HashMap<String, serde_json::Value>) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn with_mime_type(mut self, mime_type: &str) -> Self {
+        self.mime_type = Some(mime_type.to_string());
+        self
+    }
+
+    /// Report the bytes sent to a progress bar, its length is set to the size
+    /// of the content.
+    pub fn with_progress(mut self, progress: ProgressBar) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    fn name(&self) -> Result<String> {
+        match (&self.name, &self.source) {
+            (Some(name), _) => Ok(name.clone()),
+            (None, Source::Path(path)) => path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .ok_or_else(|| eyre!("{} does not have a file name", path.display())),
+            (None, Source::Reader(_)) => Err(eyre!("a name is required to upload a stream")),
+        }
+    }
 }
 
 impl Namespaced for ContentUpload<'_> {
@@ -57,23 +125,55 @@ impl Namespaced for ContentUpload<'_> {
 }
 
 impl Upload for ContentUpload<'_> {
+    type Response = UploadResponse;
+
     async fn form(&self) -> Result<multipart::Form> {
-        let fname = self
-            .path
-            .file_name()
-            .unwrap()
-            .to_string_lossy()
-            .into_owned();
-        let mime = mime_guess::from_path(self.path).first_or_octet_stream();
-        let fobj = File::open(self.path).await?;
-
-        let stream = FramedRead::new(fobj, BytesCodec::new());
-        let body = Body::wrap_stream(stream);
-        let part = multipart::Part::stream(body)
-            .file_name(fname)
-            .mime_str(mime.as_ref())
-            .unwrap();
-        let form = multipart::Form::new().part("file", part);
+        let fname = self.name()?;
+        let mime = match &self.mime_type {
+            Some(mime) => mime.clone(),
+            None => mime_guess::from_path(&fname)
+                .first_or_octet_stream()
+                .to_string(),
+        };
+
+        let part = match &self.source {
+            Source::Path(path) => {
+                let fobj = File::open(path).await?;
+                let size = fobj.metadata().await?.len();
+
+                // The form is rebuilt when a request is retried, start over.
+                let progress = self.progress.clone().unwrap_or_else(ProgressBar::hidden);
+                progress.set_length(size);
+                progress.set_position(0);
+
+                let stream = FramedRead::new(fobj, BytesCodec::new())
+                    .inspect_ok(move |chunk| progress.inc(chunk.len() as u64));
+
+                multipart::Part::stream_with_length(Body::wrap_stream(stream), size)
+            }
+            Source::Reader(reader) => {
+                let Some(reader) = reader.lock().unwrap().take() else {
+                    return Err(eyre!(
+                        "{} has already been read, it can't be retried",
+                        fname
+                    ));
+                };
+
+                // The length isn't known up front, only the bytes sent are.
+                let progress = self.progress.clone().unwrap_or_else(ProgressBar::hidden);
+
+                let stream = FramedRead::new(reader, BytesCodec::new())
+                    .inspect_ok(move |chunk| progress.inc(chunk.len() as u64));
+
+                multipart::Part::stream(Body::wrap_stream(stream))
+            }
+        };
+
+        let part = part.file_name(fname).mime_str(&mime)?;
+        let mut form = multipart::Form::new().part("file", part);
+        if !self.labels.is_empty() {
+            form = form.text("labels", serde_json::to_string(&self.labels)?);
+        }
 
         Ok(form)
     }
@@ -83,6 +183,12 @@ impl Upload for ContentUpload<'_> {
     }
 }
 
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UploadResponse {
+    #[serde(default)]
+    pub content_id: String,
+}
+
 pub struct Download;
 
 impl Namespaced for Download {
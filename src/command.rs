@@ -15,6 +15,18 @@ pub trait Command: Debug + Send + Sync + Container {
     fn post_run(&self) -> Result<()> {
         Ok(())
     }
+
+    /// Whether `--watch` can run the command again, only lists and gets that
+    /// don't change anything can.
+    fn watchable(&self) -> bool {
+        false
+    }
+
+    /// Exit code when the command fails. Commands that use 1 to report their
+    /// result, like `diff`, need another one for errors.
+    fn failure_code(&self) -> i32 {
+        1
+    }
 }
 
 // Allows recursion through subcommands. Adding `derive::Command` to the primary
@@ -25,3 +37,16 @@ pub trait Container {
         None
     }
 }
+
+/// Returned by commands that report their result through the exit code, such
+/// as `diff`. Nothing is printed, the process exits with the code.
+#[derive(Debug)]
+pub struct Exit(pub i32);
+
+impl std::fmt::Display for Exit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "exit code {}", self.0)
+    }
+}
+
+impl std::error::Error for Exit {}
@@ -0,0 +1,363 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    ffi::OsString,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use eyre::{eyre, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use tabled::Tabled;
+
+use crate::{derive::Columns, output};
+
+pub static CONFIG_VAR: &str = "INDEXIFY_CONFIG";
+pub static CONTEXT_VAR: &str = "INDEXIFY_CONTEXT";
+
+static AUTH_VARS: [&str; 4] = [
+    "INDEXIFY_TOKEN",
+    "INDEXIFY_TOKEN_FILE",
+    "INDEXIFY_TOKEN_COMMAND",
+    "INDEXIFY_API_KEY",
+];
+
+static AUTH_FLAGS: [&str; 4] = ["--token", "--token-file", "--token-command", "--api-key"];
+
+/// Named server contexts, stored in `~/.config/indexify/config.yaml` by
+/// default.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Config {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_context: Option<String>,
+    #[serde(default)]
+    pub contexts: BTreeMap<String, Context>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Context {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_server: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_file: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_header: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<output::Kind>,
+}
+
+/// Row for `config get-contexts`.
+#[derive(Debug, Serialize, Tabled, Columns)]
+pub struct ContextSummary {
+    #[tabled(rename = "current")]
+    #[serde(skip)]
+    pub marker: &'static str,
+    pub name: String,
+    pub api_server: output::tabled::Option<String>,
+    pub namespace: output::tabled::Option<String>,
+    pub output: output::tabled::Option<output::Kind>,
+}
+
+impl Config {
+    /// `$INDEXIFY_CONFIG`, falling back to the XDG config directory.
+    pub fn path() -> Result<PathBuf> {
+        if let Some(path) = env::var_os(CONFIG_VAR) {
+            return Ok(PathBuf::from(path));
+        }
+
+        let base = match env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME").ok_or_else(|| eyre!("$HOME is not set"))?)
+                .join(".config"),
+        };
+
+        Ok(base.join("indexify").join("config.yaml"))
+    }
+
+    /// Load the config, a missing file is the same as an empty one.
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = match fs::read_to_string(path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).wrap_err_with(|| format!("unable to read {}", path.display())),
+        };
+
+        serde_path_to_error::deserialize(serde_yaml::Deserializer::from_str(&raw))
+            .wrap_err_with(|| format!("invalid config {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        // Contexts can contain credentials, the file is private before
+        // anything is written to it. The mode only applies to new files.
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let mut file = options
+            .open(path)
+            .wrap_err_with(|| format!("unable to write {}", path.display()))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+
+        file.write_all(serde_yaml::to_string(self)?.as_bytes())
+            .wrap_err_with(|| format!("unable to write {}", path.display()))?;
+
+        Ok(())
+    }
+
+    pub fn context(&self, name: &str) -> Result<&Context> {
+        self.contexts
+            .get(name)
+            .ok_or_else(|| eyre!("context not found: {}", name))
+    }
+
+    pub fn summaries(&self) -> Vec<ContextSummary> {
+        self.contexts
+            .iter()
+            .map(|(name, ctx)| ContextSummary {
+                marker: if self.current_context.as_ref() == Some(name) {
+                    "*"
+                } else {
+                    ""
+                },
+                name: name.clone(),
+                api_server: ctx.api_server.clone().into(),
+                namespace: ctx.namespace.clone().into(),
+                output: ctx.output.clone().into(),
+            })
+            .collect()
+    }
+
+    /// A copy of the config that is safe to print.
+    pub fn redacted(&self) -> Self {
+        let mut config = self.clone();
+        for ctx in config.contexts.values_mut() {
+            // Commands can have the token inline.
+            for secret in [&mut ctx.token, &mut ctx.token_command, &mut ctx.api_key] {
+                if secret.is_some() {
+                    *secret = Some("REDACTED".to_string());
+                }
+            }
+        }
+
+        config
+    }
+}
+
+impl Context {
+    /// Merge the set fields of `other` into this context.
+    pub fn merge(&mut self, other: Context) {
+        macro_rules! merge {
+            ($($field:ident),*) => {
+                $(if other.$field.is_some() {
+                    self.$field = other.$field;
+                })*
+            };
+        }
+
+        merge!(
+            api_server,
+            namespace,
+            token,
+            token_file,
+            token_command,
+            api_key,
+            api_key_header,
+            output
+        );
+    }
+
+    fn vars(&self) -> Vec<(&'static str, String)> {
+        let values = [
+            ("INDEXIFY_API_SERVER", self.api_server.clone()),
+            ("INDEXIFY_NAMESPACE", self.namespace.clone()),
+            (
+                "INDEXIFY_OUTPUT",
+                self.output.as_ref().map(|o| o.to_string()),
+            ),
+            ("INDEXIFY_TOKEN", self.token.clone()),
+            (
+                "INDEXIFY_TOKEN_FILE",
+                self.token_file
+                    .as_ref()
+                    .map(|p| p.to_string_lossy().into_owned()),
+            ),
+            ("INDEXIFY_TOKEN_COMMAND", self.token_command.clone()),
+            ("INDEXIFY_API_KEY", self.api_key.clone()),
+            ("INDEXIFY_API_KEY_HEADER", self.api_key_header.clone()),
+        ];
+
+        values
+            .into_iter()
+            .filter_map(|(k, v)| v.map(|v| (k, v)))
+            .collect()
+    }
+}
+
+/// Expose the active context as environment variables. This must happen before
+/// the CLI is parsed so that flags and variables the user has set take
+/// precedence over the context. Credentials are treated as a unit, if any of
+/// them are set in the environment or as flags none are taken from the context.
+pub fn activate(args: &[OsString]) -> Result<()> {
+    let path = match arg_value(args, "--config") {
+        Some(path) => PathBuf::from(path),
+        None => Config::path()?,
+    };
+
+    let config = Config::load(&path)?;
+
+    let name = arg_value(args, "--context")
+        .or_else(|| env::var(CONTEXT_VAR).ok())
+        .or(config.current_context.clone());
+
+    let Some(name) = name else {
+        return Ok(());
+    };
+
+    let user_auth = AUTH_VARS.iter().any(|var| env::var_os(var).is_some())
+        || AUTH_FLAGS
+            .iter()
+            .any(|flag| arg_value(args, flag).is_some());
+
+    for (var, value) in config.context(&name)?.vars() {
+        if env::var_os(var).is_some() || (user_auth && AUTH_VARS.contains(&var)) {
+            continue;
+        }
+
+        env::set_var(var, value);
+    }
+
+    Ok(())
+}
+
+// The context has to be known before the CLI is parsed, look for `--flag value`
+// or `--flag=value` in the raw arguments.
+fn arg_value(args: &[OsString], flag: &str) -> Option<String> {
+    let prefix = format!("{}=", flag);
+    let mut args = args.iter().filter_map(|a| a.to_str());
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+
+        if arg == flag {
+            return args.next().map(String::from);
+        }
+
+        if let Some(value) = arg.strip_prefix(&prefix) {
+            return Some(value.to_string());
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn redacted_hides_secrets() {
+        let config = Config {
+            contexts: BTreeMap::from([(
+                "prod".to_string(),
+                Context {
+                    token: Some("secret".to_string()),
+                    token_file: Some(PathBuf::from("/run/token")),
+                    token_command: Some("echo secret".to_string()),
+                    api_key: Some("secret".to_string()),
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+
+        let yaml = serde_yaml::to_string(&config.redacted()).unwrap();
+
+        assert!(!yaml.contains("secret"), "{}", yaml);
+        assert!(yaml.contains("/run/token"), "{}", yaml);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn save_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = env::temp_dir().join(format!("indexify-config-{}", std::process::id()));
+        let path = dir.join("config.yaml");
+
+        // Existing files are made private too.
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        Config::default().save(&path).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn arg_value_separate() {
+        let args = args(&["indexify", "--context", "prod", "content", "list"]);
+
+        assert_eq!(arg_value(&args, "--context"), Some("prod".to_string()));
+    }
+
+    #[test]
+    fn arg_value_equals() {
+        let args = args(&["indexify", "content", "list", "--context=prod"]);
+
+        assert_eq!(arg_value(&args, "--context"), Some("prod".to_string()));
+    }
+
+    #[test]
+    fn arg_value_missing() {
+        assert_eq!(
+            arg_value(&args(&["indexify", "--context"]), "--context"),
+            None
+        );
+        assert_eq!(arg_value(&args(&["indexify", "list"]), "--context"), None);
+    }
+
+    #[test]
+    fn arg_value_stops_at_double_dash() {
+        let args = args(&["indexify", "--", "--context", "prod"]);
+
+        assert_eq!(arg_value(&args, "--context"), None);
+    }
+
+    #[test]
+    fn arg_value_needs_the_whole_flag() {
+        let args = args(&["indexify", "--contexts", "prod", "--context-x=a"]);
+
+        assert_eq!(arg_value(&args, "--context"), None);
+    }
+}
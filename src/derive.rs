@@ -1 +1 @@
-pub use cli_derive::Command;
+pub use cli_derive::{Columns, Command};
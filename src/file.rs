@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use clap::{
     builder::{TypedValueParser, ValueParserFactory},
     error::ErrorKind,
@@ -5,21 +7,13 @@ use clap::{
 use eyre::{eyre, Result, WrapErr};
 use serde::de::DeserializeOwned;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum File<T> {
+    #[default]
     None,
     Some(T),
 }
 
-impl<T> Default for File<T>
-where
-    T: Sync,
-{
-    fn default() -> Self {
-        Self::None
-    }
-}
-
 impl<T> ValueParserFactory for File<T>
 where
     T: Sync,
@@ -70,3 +64,53 @@ where
             .map_err(|e| cmd.clone().error(ErrorKind::InvalidValue, format!("{}", e)))
     }
 }
+
+/// Read every document in a file, or in all the YAML and JSON files in a
+/// directory. YAML files can hold multiple documents separated by `---`.
+pub fn documents<T>(path: &Path) -> Result<Vec<T>>
+where
+    T: DeserializeOwned,
+{
+    if path.is_dir() {
+        let mut paths = std::fs::read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        paths.sort();
+
+        let mut docs = vec![];
+        for path in paths {
+            let mime = mime_guess::from_path(&path).first_or_octet_stream();
+
+            if path.is_file() && matches!(mime.subtype().as_str(), "x-yaml" | "json") {
+                docs.extend(documents(&path)?);
+            }
+        }
+
+        return Ok(docs);
+    }
+
+    let raw = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("unable to read {}", path.display()))?;
+
+    match mime_guess::from_path(path)
+        .first_or_octet_stream()
+        .subtype()
+        .as_str()
+    {
+        "x-yaml" => serde_yaml::Deserializer::from_str(&raw)
+            .map(|doc| {
+                serde_path_to_error::deserialize(doc)
+                    .wrap_err_with(|| format!("Invalid YAML in {}", path.display()))
+            })
+            .collect(),
+        "json" => Ok(vec![serde_path_to_error::deserialize(
+            &mut serde_json::Deserializer::from_str(&raw),
+        )
+        .wrap_err_with(|| format!("Invalid JSON in {}", path.display()))?]),
+        unsupported => Err(eyre!(
+            "Unsupported file type for {}: {}",
+            path.display(),
+            unsupported
+        )),
+    }
+}
@@ -4,28 +4,80 @@ mod api;
 mod cli;
 mod client;
 mod command;
+mod config;
 mod derive;
 mod file;
 mod output;
+mod prompt;
 mod telemetry;
 
 use clap::Parser;
-use eyre::{Report, Result};
+use eyre::{eyre, Report, Result};
 use futures::future::{BoxFuture, FutureExt};
 
 use crate::{cli::root::Root, command::Command};
 
-#[tokio::main]
-async fn main() -> Result<(), Report> {
+fn main() -> Result<(), Report> {
     color_eyre::config::HookBuilder::default()
         .display_env_section(false)
         .display_location_section(false)
         .install()?;
 
-    execute(&Root::parse()).await
+    // Modifies the environment, this needs to happen before the runtime has
+    // started any threads.
+    // A broken config shouldn't stop the `config` commands that repair it.
+    if let Err(e) = config::activate(&std::env::args_os().collect::<Vec<_>>()) {
+        eprintln!("warning: not using a context, {:#}", e);
+    }
+
+    let root = Root::parse();
+
+    // Checked before anything runs, commands that change something would do
+    // it again at every interval.
+    if root.watch.is_some() && !leaf(&root).watchable() {
+        return Err(eyre!("--watch is only supported by list and get commands"));
+    }
+
+    let result = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(async {
+            execute(&root).await?;
+
+            match root.watch {
+                Some(interval) => watch(&root, interval).await,
+                None => Ok(()),
+            }
+        });
+
+    let Err(report) = result else {
+        return Ok(());
+    };
+
+    if let Some(command::Exit(code)) = report.downcast_ref() {
+        std::process::exit(*code);
+    }
+
+    match leaf(&root).failure_code() {
+        1 => Err(report),
+        code => {
+            eprintln!("Error: {:?}", report);
+            std::process::exit(code);
+        }
+    }
+}
+
+// The subcommand that was picked, the one that does the work.
+fn leaf(root: &Root) -> &dyn Command {
+    let mut cmd: &dyn Command = root;
+    while let Some(next) = cmd.next() {
+        cmd = next;
+    }
+
+    cmd
 }
 
-fn execute(cmd: &dyn Command) -> BoxFuture<Result<()>> {
+fn execute(cmd: &dyn Command) -> BoxFuture<'_, Result<()>> {
     async move {
         cmd.pre_run()?;
 
@@ -39,3 +91,18 @@ fn execute(cmd: &dyn Command) -> BoxFuture<Result<()>> {
     }
     .boxed()
 }
+
+// Runs the command that was picked again at every interval, it is up to
+// `output::Format::list` to print only what changed.
+async fn watch(root: &Root, interval: std::time::Duration) -> Result<()> {
+    let cmd = leaf(root);
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        // The server can be briefly unavailable, the next poll may work.
+        if let Err(e) = cmd.run().await {
+            eprintln!("warning: {:#}", e);
+        }
+    }
+}
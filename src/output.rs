@@ -1,36 +1,785 @@
+pub mod jsonpath;
 pub mod tabled;
+pub mod watch;
 
-use ::tabled::{Table, Tabled};
-use clap::ValueEnum;
-use eyre::Result;
-use serde::Serialize;
+use std::{cmp::Ordering, fmt, io::IsTerminal, str::FromStr, time::Duration};
 
-#[derive(ValueEnum, Debug, Default, Clone, Serialize)]
-#[serde(rename_all = "kebab-case")]
-pub enum Format {
+use ::tabled::{builder::Builder, Table, Tabled};
+use clap::{ArgMatches, FromArgMatches};
+use eyre::{eyre, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+static KINDS: &str = "pretty, wide, json, yaml, jsonl, csv, tsv, name, \
+                      custom-columns=NAME:.path,... or jsonpath=TEMPLATE";
+
+/// The value of `--output`.
+#[derive(Debug, Default, Clone)]
+pub enum Kind {
     #[default]
     Pretty,
+    /// Tables with every field, including the ones that are skipped by default.
+    Wide,
     Json,
+    Yaml,
+    /// One compact JSON object per line.
+    Jsonl,
+    Csv,
+    Tsv,
+    /// Only the `id` column of each item, or `name` when the table doesn't
+    /// show an id.
+    Name,
+    CustomColumns(Vec<Column>),
+    /// Lists are wrapped in an object as `items`, so `{.items[*].id}` prints
+    /// every id.
+    JsonPath(jsonpath::Template),
 }
 
-impl Format {
-    pub fn list(&self, data: &[impl Serialize + Tabled]) -> Result<()> {
+/// How a command prints its results, built from the output flags on `Root`.
+#[derive(Debug, Default, Clone)]
+pub struct Format {
+    pub kind: Kind,
+    /// Columns of tables, any field can be picked including skipped ones.
+    pub columns: Vec<String>,
+    /// Field, or `.path` to a nested field, that lists are sorted by.
+    pub sort_by: Option<String>,
+    pub reverse: bool,
+    /// Interval lists are polled at, they only print what changed.
+    pub watch: Option<Duration>,
+}
+
+/// A column of `custom-columns` output, `NAME:.path.to[0].field`.
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub name: String,
+    pub path: String,
+}
+
+// Paths are turned into JSON pointers, `.a.b[0]` becomes `/a/b/0`.
+fn pointer(path: &str) -> String {
+    path.trim_start_matches('.')
+        .replace('[', ".")
+        .replace(']', "")
+        .split('.')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| format!("/{}", segment))
+        .collect()
+}
+
+/// The field shown under each header of a table, as a `.path` into the
+/// serialized item, in the order of `Tabled::headers`. Lets `--columns`,
+/// `--sort-by` and `--wide` agree on what a column is when tables rename
+/// fields. Derived with `derive::Columns`.
+pub trait Columns: Tabled {
+    fn paths() -> Vec<&'static str>;
+}
+
+impl<T: Columns> Columns for &T {
+    fn paths() -> Vec<&'static str> {
+        T::paths()
+    }
+}
+
+impl FromStr for Kind {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(spec) = s.strip_prefix("custom-columns=") {
+            let columns = spec
+                .split(',')
+                .map(|column| match column.split_once(':') {
+                    Some((name, path)) if !name.is_empty() && path.starts_with('.') => Ok(Column {
+                        name: name.to_string(),
+                        path: path.to_string(),
+                    }),
+                    _ => Err(eyre!("invalid column {}, expected NAME:.path", column)),
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            return Ok(Kind::CustomColumns(columns));
+        }
+
+        if let Some(template) = s.strip_prefix("jsonpath=") {
+            return Ok(Kind::JsonPath(jsonpath::Template::parse(template)?));
+        }
+
+        match s {
+            "pretty" => Ok(Kind::Pretty),
+            "wide" => Ok(Kind::Wide),
+            "json" => Ok(Kind::Json),
+            "yaml" => Ok(Kind::Yaml),
+            "jsonl" => Ok(Kind::Jsonl),
+            "csv" => Ok(Kind::Csv),
+            "tsv" => Ok(Kind::Tsv),
+            "name" => Ok(Kind::Name),
+            _ => Err(eyre!("unknown output format {}, expected {}", s, KINDS)),
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Format::Pretty => println!("{}", Table::new(data)),
-            Format::Json => println!("{}", serde_json::to_string_pretty(&data).unwrap()),
+            Kind::Pretty => write!(f, "pretty"),
+            Kind::Wide => write!(f, "wide"),
+            Kind::Json => write!(f, "json"),
+            Kind::Yaml => write!(f, "yaml"),
+            Kind::Jsonl => write!(f, "jsonl"),
+            Kind::Csv => write!(f, "csv"),
+            Kind::Tsv => write!(f, "tsv"),
+            Kind::Name => write!(f, "name"),
+            Kind::CustomColumns(columns) => write!(
+                f,
+                "custom-columns={}",
+                columns
+                    .iter()
+                    .map(|c| format!("{}:{}", c.name, c.path))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            Kind::JsonPath(template) => write!(f, "jsonpath={}", template),
+        }
+    }
+}
+
+// Formats are stored in contexts the same way they're passed to --output.
+impl Serialize for Kind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Kind {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromArgMatches for Format {
+    fn from_arg_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+        std::result::Result::Ok(Self {
+            kind: matches
+                .get_one::<Kind>("output")
+                .cloned()
+                .unwrap_or_default(),
+            columns: matches
+                .get_many::<String>("columns")
+                .map(|columns| columns.cloned().collect())
+                .unwrap_or_default(),
+            sort_by: matches.get_one::<String>("sort_by").cloned(),
+            reverse: matches.get_flag("reverse"),
+            watch: matches.get_one::<Duration>("watch").copied(),
+        })
+    }
+
+    fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), clap::Error> {
+        *self = Self::from_arg_matches(matches)?;
+
+        std::result::Result::Ok(())
+    }
+}
+
+// All the arguments are globals defined on Root, there's nothing to add.
+impl clap::Args for Format {
+    fn augment_args(cmd: clap::Command) -> clap::Command {
+        cmd
+    }
+
+    fn augment_args_for_update(cmd: clap::Command) -> clap::Command {
+        cmd
+    }
+}
+
+// Strings are shown without the quotes JSON would add.
+fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(value)) => value.clone(),
+        Some(value) => value.to_string(),
+    }
+}
+
+// Numbers are compared as numbers, everything else as it is displayed.
+fn compare(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    match (a.and_then(Value::as_f64), b.and_then(Value::as_f64)) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        _ => cell(a).cmp(&cell(b)),
+    }
+}
+
+// The headers and rows of a table. Fields the table shows keep its formatting
+// unless `raw` is set, like it is for csv and tsv which are read by programs
+// rather than people. Everything else is taken from the serialized item.
+fn cells<T>(
+    data: &[T],
+    columns: &[String],
+    wide: bool,
+    raw: bool,
+) -> Result<(Vec<String>, Vec<Vec<String>>)>
+where
+    T: Serialize + Columns,
+{
+    let headers: Vec<String> = T::headers().iter().map(|h| h.to_string()).collect();
+    let values = data
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The fields headers show, renamed ones aren't added again under their
+    // own name.
+    let paths = T::paths();
+    let shown: Vec<&str> = paths
+        .iter()
+        .filter_map(|p| p.trim_start_matches('.').split('.').next())
+        .collect();
+
+    let mut all = headers.clone();
+    if wide || !columns.is_empty() {
+        for fields in values.iter().filter_map(Value::as_object) {
+            for key in fields.keys() {
+                if !all.contains(key) && !shown.contains(&key.as_str()) {
+                    all.push(key.clone());
+                }
+            }
+        }
+    }
+
+    let selected = match columns {
+        [] => all,
+        columns => columns
+            .iter()
+            .map(|column| {
+                // Renamed fields can be picked by either name.
+                let renamed = paths
+                    .iter()
+                    .position(|p| p.trim_start_matches('.') == column)
+                    .map(|i| headers[i].clone());
+
+                let known = all.contains(column) || values.iter().any(|v| v.get(column).is_some());
+
+                match renamed {
+                    Some(header) => Ok(header),
+                    None if data.is_empty() || known => Ok(column.clone()),
+                    None => Err(eyre!(
+                        "unknown column {}, expected one of {}",
+                        column,
+                        all.join(", ")
+                    )),
+                }
+            })
+            .collect::<Result<_>>()?,
+    };
+
+    let rows = data
+        .iter()
+        .zip(&values)
+        .map(|(item, value)| {
+            let fields = item.fields();
+
+            selected
+                .iter()
+                .map(|column| match headers.iter().position(|h| h == column) {
+                    // Fields that aren't serialized only have their display.
+                    Some(i) => match value.pointer(&pointer(paths[i])) {
+                        Some(field) if raw => cell(Some(field)),
+                        _ => fields[i].to_string(),
+                    },
+                    None => cell(value.get(column)),
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok((selected, rows))
+}
+
+// Items are identified by their id, or name when the table doesn't show an id.
+fn key<T: Tabled>() -> Option<usize> {
+    let headers = T::headers();
+
+    ["id", "name"]
+        .iter()
+        .find_map(|name| headers.iter().position(|h| h == name))
+}
+
+// The server can hand out a start id along with the last page, there is no
+// next page once all of the total has been shown.
+fn next_page(shown: usize, total: Option<u64>, start_id: Option<String>) -> Option<String> {
+    let start_id = start_id.filter(|_| total.is_none_or(|t| (shown as u64) < t))?;
+
+    Some(format!(
+        "Showing {} of {} items. Pass --start-id {} for the next page, or --all.",
+        shown,
+        total.map_or("more".to_string(), |t| t.to_string()),
+        start_id,
+    ))
+}
+
+fn table(headers: Vec<String>, rows: Vec<Vec<String>>) -> Table {
+    let mut builder = Builder::default();
+
+    builder.push_record(headers);
+    for row in rows {
+        builder.push_record(row);
+    }
+
+    builder.build()
+}
+
+// Every field of an item on its own row, nested values are shown as YAML.
+// Scalar fields use the table's rendering, so times and sizes read the same as
+// they do in lists.
+fn describe<T: Tabled>(value: &Value, data: &T) -> Result<Table> {
+    let mut builder = Builder::default();
+    let rendered: Vec<_> = T::headers().into_iter().zip(data.fields()).collect();
+
+    let Value::Object(fields) = value else {
+        builder.push_record([cell(Some(value))]);
+
+        return Ok(builder.build());
+    };
+
+    for (key, value) in fields {
+        let value = match value {
+            Value::Array(items) if items.is_empty() => String::new(),
+            Value::Object(map) if map.is_empty() => String::new(),
+            Value::Array(_) | Value::Object(_) => {
+                serde_yaml::to_string(value)?.trim_end().to_string()
+            }
+            value => match rendered.iter().find(|(header, _)| header == key) {
+                Some((_, field)) => field.to_string(),
+                None => cell(Some(value)),
+            },
+        };
+
+        builder.push_record([key.clone(), value]);
+    }
+
+    Ok(builder.build())
+}
+
+impl Format {
+    /// Whether the output is meant for people rather than other programs.
+    pub fn pretty(&self) -> bool {
+        matches!(self.kind, Kind::Pretty | Kind::Wide)
+    }
+
+    /// Points to the page after a list when there is one. Printed on stderr,
+    /// keeping stdout to the items themselves.
+    pub fn next_page(&self, shown: usize, total: Option<u64>, start_id: Option<String>) {
+        if let Some(hint) = next_page(shown, total, start_id).filter(|_| self.pretty()) {
+            eprintln!("{}", hint);
+        }
+    }
+
+    /// Progress bars are drawn on stderr, they'd only get in the way when it
+    /// isn't a terminal or the output is meant for another program.
+    pub fn progress(&self) -> bool {
+        self.pretty() && std::io::stderr().is_terminal()
+    }
+
+    pub fn list<T>(&self, data: &[T]) -> Result<()>
+    where
+        T: Serialize + Columns,
+    {
+        let mut items: Vec<&T> = data.iter().collect();
+
+        if let Some(field) = &self.sort_by {
+            let values = data
+                .iter()
+                .map(serde_json::to_value)
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let header = field.trim_start_matches('.');
+            let pointer = match T::headers().iter().position(|h| h == header) {
+                Some(i) => pointer(T::paths()[i]),
+                None => pointer(field),
+            };
+
+            if !values.is_empty() && values.iter().all(|v| v.pointer(&pointer).is_none()) {
+                return Err(eyre!("unknown field to sort by: {}", field));
+            }
+
+            let mut order: Vec<usize> = (0..data.len()).collect();
+            order.sort_by(|&a, &b| {
+                compare(values[a].pointer(&pointer), values[b].pointer(&pointer))
+            });
+
+            items = order.into_iter().map(|i| &data[i]).collect();
+        }
+
+        if self.reverse {
+            items.reverse();
+        }
+
+        match self.watch {
+            Some(_) => self.watch(&items),
+            None => self.print(&items),
+        }
+    }
+
+    // Each poll of `--watch`. Tables are redrawn with new rows in green and
+    // changed ones in yellow, jsonl only has events for what changed and the
+    // other formats are printed as they are.
+    fn watch<T>(&self, data: &[T]) -> Result<()>
+    where
+        T: Serialize + Columns,
+    {
+        let Some(column) = key::<T>() else {
+            return Err(eyre!("--watch isn't supported here"));
+        };
+
+        let values = data
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let poll = watch::poll(
+            data.iter()
+                .map(|item| item.fields()[column].to_string())
+                .zip(values.iter().cloned())
+                .collect(),
+        );
+
+        match &self.kind {
+            Kind::Jsonl => {
+                let events = poll
+                    .changes
+                    .iter()
+                    .zip(&values)
+                    .filter_map(|(change, value)| match change {
+                        watch::Change::Added => Some(("added", value)),
+                        watch::Change::Modified => Some(("modified", value)),
+                        watch::Change::Unchanged => None,
+                    })
+                    .chain(poll.deleted.iter().map(|value| ("deleted", value)));
+
+                for (kind, value) in events {
+                    println!("{}", serde_json::json!({ "type": kind, "object": value }));
+                }
+            }
+            Kind::Pretty | Kind::Wide => {
+                let (headers, rows) =
+                    cells(data, &self.columns, matches!(self.kind, Kind::Wide), false)?;
+
+                // Every row spans as many lines as its tallest cell and is
+                // followed by a border.
+                let heights: Vec<_> = rows
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|c| c.lines().count())
+                            .max()
+                            .unwrap_or(0)
+                            .max(1)
+                    })
+                    .collect();
+                let rendered = table(headers, rows).to_string();
+                let mut lines = rendered.lines();
+
+                let term = console::Term::stdout();
+                if term.is_term() {
+                    term.clear_screen()?;
+                }
+
+                // The top border, the header and the border below it.
+                for line in lines.by_ref().take(3) {
+                    println!("{}", line);
+                }
+
+                for (change, height) in poll.changes.iter().zip(heights) {
+                    let style = match change {
+                        _ if poll.first => console::Style::new(),
+                        watch::Change::Added => console::Style::new().green(),
+                        watch::Change::Modified => console::Style::new().yellow(),
+                        watch::Change::Unchanged => console::Style::new(),
+                    };
+
+                    for line in lines.by_ref().take(height) {
+                        println!("{}", style.apply_to(line));
+                    }
+
+                    for line in lines.by_ref().take(1) {
+                        println!("{}", line);
+                    }
+                }
+            }
+            _ => self.print(data)?,
         }
 
         Ok(())
     }
 
-    // TODO: this should probably be a separate pretty implementation from
-    // list(Vec<T>).
-    pub fn item(&self, data: &(impl Serialize + Tabled)) -> Result<()> {
-        match self {
-            Format::Pretty => self.list(&[data])?,
-            Format::Json => println!("{}", serde_json::to_string_pretty(data).unwrap()),
+    fn print<T>(&self, data: &[T]) -> Result<()>
+    where
+        T: Serialize + Columns,
+    {
+        match &self.kind {
+            Kind::Pretty if self.columns.is_empty() => println!("{}", Table::new(data)),
+            Kind::Pretty | Kind::Wide => {
+                let (headers, rows) =
+                    cells(data, &self.columns, matches!(self.kind, Kind::Wide), false)?;
+
+                println!("{}", table(headers, rows));
+            }
+            Kind::Json => println!("{}", serde_json::to_string_pretty(&data).unwrap()),
+            Kind::Yaml => print!("{}", serde_yaml::to_string(&data)?),
+            Kind::Jsonl => {
+                for item in data {
+                    println!("{}", serde_json::to_string(item)?);
+                }
+            }
+            Kind::Csv | Kind::Tsv => {
+                let delimiter = match self.kind {
+                    Kind::Tsv => b'\t',
+                    _ => b',',
+                };
+
+                let mut writer = csv::WriterBuilder::new()
+                    .delimiter(delimiter)
+                    .from_writer(std::io::stdout());
+
+                let (headers, rows) = cells(data, &self.columns, false, true)?;
+                writer.write_record(headers)?;
+                for row in rows {
+                    writer.write_record(row)?;
+                }
+
+                writer.flush()?;
+            }
+            Kind::Name => {
+                let Some(column) = key::<T>() else {
+                    return Err(eyre!("name output isn't supported here"));
+                };
+
+                for item in data {
+                    println!("{}", item.fields()[column]);
+                }
+            }
+            Kind::CustomColumns(columns) => {
+                let rows = data
+                    .iter()
+                    .map(|item| {
+                        let value = serde_json::to_value(item)?;
+
+                        Ok(columns
+                            .iter()
+                            .map(|c| cell(value.pointer(&pointer(&c.path))))
+                            .collect())
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                println!(
+                    "{}",
+                    table(columns.iter().map(|c| c.name.clone()).collect(), rows)
+                );
+            }
+            Kind::JsonPath(template) => {
+                println!("{}", template.render(&serde_json::json!({ "items": data })))
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Print something that is meant to be read back in, such as an exported
+    /// graph. A table would lose information, so pretty output is YAML.
+    pub fn document(&self, data: &impl Serialize) -> Result<()> {
+        match &self.kind {
+            Kind::Json => println!("{}", serde_json::to_string_pretty(data)?),
+            Kind::Jsonl => println!("{}", serde_json::to_string(data)?),
+            Kind::JsonPath(template) => {
+                println!("{}", template.render(&serde_json::to_value(data)?))
+            }
+            _ => print!("{}", serde_yaml::to_string(data)?),
         }
 
         Ok(())
     }
+
+    /// A single item as a row of the same table `list` would print, see
+    /// `describe` for all of its fields.
+    pub fn item(&self, data: &(impl Serialize + Columns)) -> Result<()> {
+        match &self.kind {
+            Kind::Json => println!("{}", serde_json::to_string_pretty(data).unwrap()),
+            Kind::Yaml => print!("{}", serde_yaml::to_string(data)?),
+            Kind::JsonPath(template) => {
+                println!("{}", template.render(&serde_json::to_value(data)?))
+            }
+            _ => self.list(&[data])?,
+        }
+
+        Ok(())
+    }
+
+    /// A titled table of what belongs to an item shown with `describe`, such
+    /// as the parameters of an extractor. Only for pretty output, other formats
+    /// describe the item as a whole.
+    pub fn describe_section<T>(&self, title: &str, data: &[T]) -> Result<()>
+    where
+        T: Serialize + Columns,
+    {
+        println!("\n{}:", title);
+
+        match data.is_empty() {
+            true => println!("  none"),
+            false => self.print(data)?,
+        }
+
+        Ok(())
+    }
+
+    /// Every field of a single item, including the ones tables skip.
+    pub fn describe(&self, data: &(impl Serialize + Columns)) -> Result<()> {
+        match self.kind {
+            Kind::Pretty | Kind::Wide => {
+                println!("{}", describe(&serde_json::to_value(data)?, data)?)
+            }
+            _ => self.item(data)?,
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::derive::Columns;
+
+    #[derive(Serialize, Tabled, Columns)]
+    struct Hit {
+        id: String,
+        #[tabled(rename = "score")]
+        confidence_score: f32,
+        #[tabled(skip)]
+        text: String,
+    }
+
+    fn hits() -> Vec<Hit> {
+        vec![Hit {
+            id: "abc".to_string(),
+            confidence_score: 0.5,
+            text: "hello".to_string(),
+        }]
+    }
+
+    fn columns(columns: &[&str]) -> Vec<String> {
+        columns.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[derive(Serialize, Tabled, Columns)]
+    struct Task {
+        id: String,
+        #[tabled(rename = "content_id")]
+        #[columns(path = ".content_metadata.id")]
+        content_metadata: Value,
+        #[tabled(skip)]
+        #[serde(rename = "state")]
+        outcome: String,
+        #[serde(rename = "type")]
+        kind: String,
+    }
+
+    #[test]
+    fn paths_of_renamed_headers() {
+        assert_eq!(Hit::paths(), vec![".id", ".confidence_score"]);
+    }
+
+    #[test]
+    fn paths_of_nested_and_serde_renamed_fields() {
+        assert_eq!(Task::paths(), vec![".id", ".content_metadata.id", ".type"]);
+    }
+
+    #[test]
+    fn wide_shows_nested_fields_once() {
+        let tasks = vec![Task {
+            id: "abc".to_string(),
+            content_metadata: json!({"id": "def"}),
+            outcome: "Success".to_string(),
+            kind: "embed".to_string(),
+        }];
+        let (headers, _) = cells(&tasks, &[], true, false).unwrap();
+
+        assert_eq!(headers, columns(&["id", "content_id", "kind", "state"]));
+    }
+
+    #[test]
+    fn wide_shows_renamed_fields_once() {
+        let (headers, rows) = cells(&hits(), &[], true, false).unwrap();
+
+        assert_eq!(headers, columns(&["id", "score", "text"]));
+        assert_eq!(rows, vec![columns(&["abc", "0.5", "hello"])]);
+    }
+
+    #[test]
+    fn columns_by_header_or_field() {
+        let (headers, rows) = cells(
+            &hits(),
+            &columns(&["confidence_score", "text"]),
+            false,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(headers, columns(&["score", "text"]));
+        assert_eq!(rows, vec![columns(&["0.5", "hello"])]);
+    }
+
+    #[derive(Serialize, Tabled, Columns)]
+    struct File {
+        name: String,
+        created_at: tabled::Timestamp,
+        size: tabled::Bytes,
+    }
+
+    #[test]
+    fn raw_cells_are_serialized_values() {
+        let files = vec![File {
+            name: "report.pdf".to_string(),
+            created_at: tabled::Timestamp(1700000000),
+            size: tabled::Bytes(2048),
+        }];
+
+        let (_, rows) = cells(&files, &[], false, true).unwrap();
+        assert_eq!(rows, vec![columns(&["report.pdf", "1700000000", "2048"])]);
+
+        let (_, rows) = cells(&files, &[], false, false).unwrap();
+        assert_eq!(rows[0][1], tabled::Timestamp(1700000000).to_string());
+        assert_eq!(rows[0][2], "2.00 KiB");
+    }
+
+    #[test]
+    fn unknown_column() {
+        assert!(cells(&hits(), &columns(&["size"]), false, false).is_err());
+    }
+
+    #[test]
+    fn next_page_hint() {
+        let hint = |total| next_page(10, total, Some("abc".to_string()));
+
+        assert_eq!(
+            hint(Some(25)).unwrap(),
+            "Showing 10 of 25 items. Pass --start-id abc for the next page, or --all."
+        );
+        assert!(hint(None).unwrap().contains("of more items"));
+        assert_eq!(hint(Some(10)), None);
+        assert_eq!(next_page(10, Some(25), None), None);
+    }
+
+    #[test]
+    fn pointer_from_path() {
+        assert_eq!(pointer(".a.b[0]"), "/a/b/0");
+        assert_eq!(pointer("id"), "/id");
+    }
 }
@@ -0,0 +1,157 @@
+use std::fmt;
+
+use eyre::{eyre, Result};
+use serde_json::Value;
+use serde_json_path::JsonPath;
+
+/// A kubectl style template, text with JSONPath expressions in braces such as
+/// `{.items[*].id}`. The leading `$` of expressions is optional, and a template
+/// without any braces is a single expression.
+#[derive(Debug, Clone)]
+pub struct Template {
+    raw: String,
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone)]
+enum Part {
+    Text(String),
+    Path(JsonPath),
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("\\n", "\n").replace("\\t", "\t")
+}
+
+fn expression(expr: &str) -> Result<JsonPath> {
+    let expr = match expr.trim() {
+        "" | "." => "$".to_string(),
+        expr if expr.starts_with('$') => expr.to_string(),
+        expr => format!("${}", expr),
+    };
+
+    JsonPath::parse(&expr).map_err(|e| eyre!("invalid JSONPath {}: {}", expr, e))
+}
+
+impl Template {
+    pub fn parse(raw: &str) -> Result<Self> {
+        if !raw.contains('{') {
+            return Ok(Self {
+                raw: raw.to_string(),
+                parts: vec![Part::Path(expression(raw)?)],
+            });
+        }
+
+        let mut parts = vec![];
+        let mut rest = raw;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                parts.push(Part::Text(unescape(&rest[..start])));
+            }
+
+            let Some(len) = rest[start..].find('}') else {
+                return Err(eyre!("unclosed {{ in {}", raw));
+            };
+
+            parts.push(Part::Path(expression(&rest[start + 1..start + len])?));
+            rest = &rest[start + len + 1..];
+        }
+
+        if !rest.is_empty() {
+            parts.push(Part::Text(unescape(rest)));
+        }
+
+        Ok(Self {
+            raw: raw.to_string(),
+            parts,
+        })
+    }
+
+    /// Expressions matching more than one value are joined with spaces.
+    pub fn render(&self, value: &Value) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.clone(),
+                Part::Path(path) => path
+                    .query(value)
+                    .all()
+                    .into_iter()
+                    .map(|v| super::cell(Some(v)))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn render(template: &str, value: &Value) -> String {
+        Template::parse(template).unwrap().render(value)
+    }
+
+    #[test]
+    fn bare_expression() {
+        let value = json!({"id": "abc", "size": 3});
+
+        assert_eq!(render(".id", &value), "abc");
+        assert_eq!(render("$.size", &value), "3");
+    }
+
+    #[test]
+    fn root() {
+        assert_eq!(render("{.}", &json!("abc")), "abc");
+        assert_eq!(render("{}", &json!(1)), "1");
+    }
+
+    #[test]
+    fn text_around_expressions() {
+        let value = json!({"id": "abc", "name": "report.pdf"});
+
+        assert_eq!(render("{.id}: {.name}\\n", &value), "abc: report.pdf\n");
+        assert_eq!(render("id\\t{.id}", &value), "id\tabc");
+    }
+
+    #[test]
+    fn several_matches_are_joined() {
+        let value = json!({"items": [{"id": "a"}, {"id": "b"}, {"id": "c"}]});
+
+        assert_eq!(render("{.items[*].id}", &value), "a b c");
+    }
+
+    #[test]
+    fn missing_and_null_are_empty() {
+        let value = json!({"id": null});
+
+        assert_eq!(render("[{.id}][{.name}]", &value), "[][]");
+    }
+
+    #[test]
+    fn objects_are_json() {
+        let value = json!({"labels": {"a": 1}});
+
+        assert_eq!(render("{.labels}", &value), r#"{"a":1}"#);
+    }
+
+    #[test]
+    fn invalid_templates() {
+        assert!(Template::parse("{.id").is_err());
+        assert!(Template::parse("{.items[}").is_err());
+    }
+
+    #[test]
+    fn display_is_the_raw_template() {
+        assert_eq!(Template::parse("{.id}\\n").unwrap().to_string(), "{.id}\\n");
+    }
+}
@@ -1,5 +1,13 @@
-use std::fmt::{self, Display};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    str::FromStr,
+    sync::OnceLock,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
+use clap::ValueEnum;
+use eyre::eyre;
 use serde::{de::Deserializer, Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize)]
@@ -40,6 +48,109 @@ impl<T> From<std::option::Option<T>> for Option<T> {
     }
 }
 
+/// How `Timestamp`s are shown in tables.
+#[derive(ValueEnum, Debug, Default, Clone, Copy)]
+pub enum TimeFormat {
+    /// How long ago, such as `3h ago`
+    #[default]
+    Relative,
+    /// RFC 3339 in UTC
+    Rfc3339,
+    /// Seconds since the Unix epoch
+    Unix,
+}
+
+static TIME_FORMAT: OnceLock<TimeFormat> = OnceLock::new();
+
+// Display can't take arguments, so the format from --time-format is set once
+// for the whole process.
+pub fn set_time_format(format: TimeFormat) {
+    let _ = TIME_FORMAT.set(format);
+}
+
+/// Seconds since the Unix epoch. Serialized as is, and shown according to
+/// `--time-format`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Timestamp(pub i64);
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // The server leaves times it doesn't know at zero.
+        if self.0 <= 0 {
+            return Ok(());
+        }
+
+        let time = UNIX_EPOCH + Duration::from_secs(self.0 as u64);
+
+        match TIME_FORMAT.get().copied().unwrap_or_default() {
+            TimeFormat::Relative => {
+                let secs = SystemTime::now()
+                    .duration_since(time)
+                    .unwrap_or_default()
+                    .as_secs();
+
+                match secs {
+                    0..60 => write!(f, "{}s ago", secs),
+                    60..3600 => write!(f, "{}m ago", secs / 60),
+                    3600..86400 => write!(f, "{}h ago", secs / 3600),
+                    _ => write!(f, "{}d ago", secs / 86400),
+                }
+            }
+            TimeFormat::Rfc3339 => write!(f, "{}", humantime::format_rfc3339_seconds(time)),
+            TimeFormat::Unix => write!(f, "{}", self.0),
+        }
+    }
+}
+
+/// Times on the command line are an RFC 3339 date or time, seconds since the
+/// Unix epoch, or a duration such as `3h` or `3h ago` meaning that long ago,
+/// the same as relative times are shown.
+impl FromStr for Timestamp {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        if let std::result::Result::Ok(secs) = s.parse::<i64>() {
+            return Ok(Self(secs));
+        }
+
+        let time = match humantime::parse_rfc3339_weak(s)
+            .or_else(|_| humantime::parse_rfc3339_weak(&format!("{}T00:00:00Z", s)))
+        {
+            std::result::Result::Ok(time) => time,
+            Err(_) => {
+                let ago = s.trim();
+                let ago = ago.strip_suffix("ago").unwrap_or(ago).trim_end();
+                let ago = humantime::parse_duration(ago).map_err(|_| {
+                    eyre!(
+                        "invalid time {}, expected an RFC 3339 time, seconds since the epoch or \
+                         a duration such as 3h",
+                        s
+                    )
+                })?;
+
+                SystemTime::now()
+                    .checked_sub(ago)
+                    .ok_or_else(|| eyre!("invalid time {}, too long ago", s))?
+            }
+        };
+
+        Ok(Self(time.duration_since(UNIX_EPOCH)?.as_secs() as i64))
+    }
+}
+
+/// A number of bytes. Serialized as is, and shown in binary units such as
+/// `2.00 KiB`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Bytes(pub u64);
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", indicatif::HumanBytes(self.0))
+    }
+}
+
 // TODO: This feels like it should be a newtype for Vec<T>
 pub fn display<T>(value: &[T]) -> String
 where
@@ -54,3 +165,84 @@ where
 
     display.join("\n")
 }
+
+// Strings are shown without the quotes JSON would add.
+pub fn labels(labels: &HashMap<String, serde_json::Value>) -> String {
+    let display = &mut labels
+        .iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::String(value) => format!("{}: {}", key, value),
+            value => format!("{}: {}", key, value),
+        })
+        .collect::<Vec<String>>();
+
+    display.sort();
+
+    display.join("\n")
+}
+
+// Long text is collapsed onto a single line and cut short to keep tables
+// readable.
+pub fn snippet(text: &str) -> String {
+    const LEN: usize = 80;
+
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    match text.char_indices().nth(LEN) {
+        Some((i, _)) => format!("{}…", &text[..i]),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64
+    }
+
+    #[test]
+    fn timestamp_unix() {
+        assert_eq!(
+            "1700000000".parse::<Timestamp>().unwrap(),
+            Timestamp(1700000000)
+        );
+    }
+
+    #[test]
+    fn timestamp_rfc3339() {
+        assert_eq!(
+            "2023-11-14T22:13:20Z".parse::<Timestamp>().unwrap(),
+            Timestamp(1700000000)
+        );
+        assert_eq!(
+            "2023-11-14".parse::<Timestamp>().unwrap(),
+            Timestamp(1699920000)
+        );
+    }
+
+    #[test]
+    fn timestamp_duration() {
+        for s in ["3h", "3h ago", "3hago", " 3h ago "] {
+            let Timestamp(secs) = s.parse().unwrap();
+
+            assert!((now() - 3 * 3600 - secs).abs() <= 1, "{}", s);
+        }
+    }
+
+    #[test]
+    fn timestamp_too_long_ago() {
+        assert!("1000000000000y".parse::<Timestamp>().is_err());
+        assert!("100y".parse::<Timestamp>().is_err());
+    }
+
+    #[test]
+    fn timestamp_invalid() {
+        assert!("ago".parse::<Timestamp>().is_err());
+        assert!("yesterday".parse::<Timestamp>().is_err());
+    }
+}
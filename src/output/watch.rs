@@ -0,0 +1,89 @@
+use std::{collections::BTreeMap, sync::Mutex};
+
+use serde_json::Value;
+
+// The items of the previous poll by key, `None` until a list has been printed.
+static PREVIOUS: Mutex<Option<BTreeMap<String, Value>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Added,
+    Modified,
+    Unchanged,
+}
+
+/// How a list differs from the previous poll. Everything is added on the
+/// first one.
+#[derive(Debug)]
+pub struct Poll {
+    pub first: bool,
+    /// The change of each item, in the order they were polled.
+    pub changes: Vec<Change>,
+    pub deleted: Vec<Value>,
+}
+
+pub fn poll(items: Vec<(String, Value)>) -> Poll {
+    let mut previous = PREVIOUS.lock().unwrap();
+    let first = previous.is_none();
+    let mut before = previous.take().unwrap_or_default();
+
+    let changes = items
+        .iter()
+        .map(|(key, value)| match before.remove(key) {
+            None => Change::Added,
+            Some(old) if old != *value => Change::Modified,
+            Some(_) => Change::Unchanged,
+        })
+        .collect();
+
+    *previous = Some(items.into_iter().collect());
+
+    Poll {
+        first,
+        changes,
+        deleted: before.into_values().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn items(items: &[(&str, Value)]) -> Vec<(String, Value)> {
+        items
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect()
+    }
+
+    // Polls share the previous list, so they're checked in a single test.
+    #[test]
+    fn poll_changes() {
+        let first = poll(items(&[("a", json!(1)), ("b", json!(2))]));
+
+        assert!(first.first);
+        assert_eq!(first.changes, vec![Change::Added, Change::Added]);
+        assert!(first.deleted.is_empty());
+
+        let second = poll(items(&[("a", json!(1)), ("b", json!(3)), ("c", json!(4))]));
+
+        assert!(!second.first);
+        assert_eq!(
+            second.changes,
+            vec![Change::Unchanged, Change::Modified, Change::Added]
+        );
+        assert!(second.deleted.is_empty());
+
+        let third = poll(items(&[("c", json!(4))]));
+
+        assert_eq!(third.changes, vec![Change::Unchanged]);
+        assert_eq!(third.deleted, vec![json!(1), json!(3)]);
+
+        let empty = poll(vec![]);
+
+        assert!(empty.changes.is_empty());
+        assert_eq!(empty.deleted, vec![json!(4)]);
+    }
+}
@@ -0,0 +1,29 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use eyre::{eyre, Result};
+
+/// Ask for confirmation before doing something destructive. Passing `yes`
+/// skips the question, which is required when stdin isn't a terminal.
+pub fn confirm(question: &str, yes: bool) -> Result<()> {
+    if yes {
+        return Ok(());
+    }
+
+    if !io::stdin().is_terminal() {
+        return Err(eyre!(
+            "{} Pass --yes to confirm when not running interactively.",
+            question
+        ));
+    }
+
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+
+    match answer.trim().to_lowercase().as_str() {
+        "y" | "yes" => Ok(()),
+        _ => Err(eyre!("Aborted")),
+    }
+}